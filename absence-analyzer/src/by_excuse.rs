@@ -1,7 +1,7 @@
 use std::{collections::HashMap, fmt::Display, hash::Hash};
 
 use anyhow::anyhow;
-use kreta_rs::client::absences::AbsenceRaw;
+use kreta_rs::model::{Absence, ExcuseStatus};
 
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
 pub struct AbsenceDetails {
@@ -25,19 +25,18 @@ impl Display for ExcuseType {
 	}
 }
 impl ExcuseType {
-	pub fn derive_from(absence: &AbsenceRaw) -> anyhow::Result<Self> {
-		match absence.excuse_status.as_ref() {
-			"Igazolt" => match &absence.excuse_type {
+	pub fn derive_from(absence: &Absence) -> anyhow::Result<Self> {
+		match &absence.excuse_status {
+			ExcuseStatus::Excused => match &absence.excuse_type {
 				Some(typ) => Ok(Self::Excused(typ.desc.clone())),
 				None => Err(anyhow!(
 					"Excused absence doesn't have excuse_type\n{absence:#?}"
 				)),
 			},
-			"Igazolando" => Ok(Self::ToBeExcused),
-			"Igazolatlan" => Ok(Self::Unexcused),
-			_ => Err(anyhow!(
-				"failed to derive excuse type: {}\n{absence:#?}",
-				absence.excuse_status
+			ExcuseStatus::ToBeExcused => Ok(Self::ToBeExcused),
+			ExcuseStatus::Unexcused => Ok(Self::Unexcused),
+			ExcuseStatus::Other(status) => Err(anyhow!(
+				"failed to derive excuse type: {status}\n{absence:#?}"
 			)),
 		}
 	}
@@ -67,7 +66,7 @@ fn push_to_absence_buf<K: Hash + Eq>(
 }
 
 pub fn absences_by_excuse_type_opt<'a>(
-	absences: impl IntoIterator<Item = &'a AbsenceRaw>,
+	absences: impl IntoIterator<Item = &'a Absence>,
 ) -> HashMap<Option<ExcuseType>, AbsenceDetails> {
 	let mut buf = HashMap::new();
 
@@ -95,7 +94,7 @@ pub fn absences_by_excuse_type_opt<'a>(
 	buf
 }
 pub fn absences_by_excuse_type<'a>(
-	absences: impl IntoIterator<Item = &'a AbsenceRaw>,
+	absences: impl IntoIterator<Item = &'a Absence>,
 ) -> AbsencesByExcuse {
 	let opt = absences_by_excuse_type_opt(absences);
 	let absences = opt
@@ -2,7 +2,7 @@ use std::{collections::HashMap, fmt::Display};
 
 use anyhow::Context;
 use chrono::{DateTime, Datelike, Duration, Utc};
-use kreta_rs::client::absences::AbsenceRaw;
+use kreta_rs::model::Absence;
 
 use crate::{AbsencesByExcuse, retreive::last_september_first_expl};
 
@@ -81,21 +81,16 @@ mod tests {
 
 // ----
 
-pub type AbsencesByWeek = HashMap<WeekNum, Vec<AbsenceRaw>>;
+pub type AbsencesByWeek = HashMap<WeekNum, Vec<Absence>>;
 pub type AbsencesByWeekAndExcuse = HashMap<WeekNum, AbsencesByExcuse>;
 
-pub fn split_by_week(iter: impl IntoIterator<Item = AbsenceRaw>) -> anyhow::Result<AbsencesByWeek> {
+pub fn split_by_week(iter: impl IntoIterator<Item = Absence>) -> anyhow::Result<AbsencesByWeek> {
 	let mut buf = HashMap::new();
 
 	for absence in iter {
-		let date: chrono::DateTime<Utc> = absence
-			.lesson
-			.start_time
-			.parse()
-			.with_context(|| format!("failed to parse {} as DateTime<Utc>", absence.date))?;
-		let weeknum = WeekNum::from_date(date);
-
-		let mut existing: Vec<AbsenceRaw> = buf.remove(&weeknum).unwrap_or_default();
+		let weeknum = WeekNum::from_date(absence.lesson_start.with_timezone(&Utc));
+
+		let mut existing: Vec<Absence> = buf.remove(&weeknum).unwrap_or_default();
 		existing.push(absence);
 		buf.insert(weeknum, existing);
 	}
@@ -104,7 +99,7 @@ pub fn split_by_week(iter: impl IntoIterator<Item = AbsenceRaw>) -> anyhow::Resu
 }
 
 pub fn split_by_week_and_excuse(
-	iter: impl IntoIterator<Item = AbsenceRaw>,
+	iter: impl IntoIterator<Item = Absence>,
 ) -> anyhow::Result<AbsencesByWeekAndExcuse> {
 	let by_week = split_by_week(iter).with_context(|| "failed to split by week")?;
 	let by_excuse = by_week
@@ -3,6 +3,8 @@ use std::{
 	hash::{DefaultHasher, Hash, Hasher},
 };
 
+use kreta_rs::client::profile::StudentProfileRaw;
+
 mod col_by_type;
 pub use col_by_type::*;
 mod total;
@@ -33,3 +35,19 @@ pub fn hash_to_color<T: Hash>(value: &T) -> Color {
 		b: (hash >> 8) as u8,
 	}
 }
+
+/// the student's name, class and school, meant to be placed at the top of the page
+pub fn profile_header(profile: &StudentProfileRaw, class_name: Option<&str>) -> String {
+	let school = match class_name {
+		Some(class_name) => format!("{class_name} - {}", profile.inst_name),
+		None => profile.inst_name.clone(),
+	};
+	format!(
+		"
+		<div style=\"display: flex; align-items: center; flex-direction: column; margin: 1rem;\">
+			<div style=\"font-size: 1.5rem; font-weight: bold;\">{}</div>
+			<div>{school}</div>
+		</div>",
+		profile.name
+	)
+}
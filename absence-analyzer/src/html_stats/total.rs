@@ -1,8 +1,16 @@
-use kreta_rs::client::absences::AbsenceRaw;
+use kreta_rs::{
+	client::{absences::AbsenceRaw, profile::StudentProfileRaw},
+	model::Absence,
+};
 
 use crate::{AbsencesByExcuse, absences_by_excuse_type, by_week::split_by_week_and_excuse};
 
 pub fn html_stats_content(iter: &[AbsenceRaw]) -> String {
+	let iter = &crate::retreive::to_model(iter);
+	html_stats_content_model(iter)
+}
+/// [html_stats_content] for absences that have already been converted
+pub fn html_stats_content_model(iter: &[Absence]) -> String {
 	let by_excuse = absences_by_excuse_type(iter);
 
 	let graph = super::by_excuse_type(&by_excuse);
@@ -34,6 +42,18 @@ pub fn html_stats(data: &[AbsenceRaw]) -> String {
 	let content = html_stats_content(data);
 	FRAME.replace("{content}", &content)
 }
+/// [html_stats] with the student's name, class and school on top
+pub fn html_stats_with_profile(
+	data: &[AbsenceRaw],
+	profile: Option<&StudentProfileRaw>,
+	class_name: Option<&str>,
+) -> String {
+	let header = profile
+		.map(|profile| super::profile_header(profile, class_name))
+		.unwrap_or_default();
+	let content = html_stats_content(data);
+	FRAME.replace("{content}", &format!("{header}{content}"))
+}
 
 pub fn forecast_html(data: &AbsencesByExcuse) -> String {
 	let forecast = crate::forecast::extract_unexcused_forecast(data);
@@ -1,6 +1,9 @@
 use anyhow::Context;
-use chrono::{DateTime, Datelike, TimeZone, Utc};
-use kreta_rs::client::{Client, absences::AbsenceRaw};
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use kreta_rs::{
+	client::{Client, absences::AbsenceRaw},
+	model::{Absence, TIMEZONE},
+};
 
 pub fn last_september_first() -> DateTime<Utc> {
 	let now = Utc::now();
@@ -16,17 +19,66 @@ pub fn last_september_first_expl(now: DateTime<Utc>) -> DateTime<Utc> {
 	Utc.with_ymd_and_hms(year, 9, 1, 0, 0, 0).unwrap()
 }
 
+/// the first day of the current school year according to kreta's school year calendar \
+/// falls back to [last_september_first] if the calendar can't be queried
+pub async fn school_year_start(client: &Client) -> NaiveDate {
+	match client.school_year_range().await {
+		Ok(range) => range.start,
+		Err(err) => {
+			eprintln!(
+				"failed to get the first day of the school year, defaulting to september 1st\n{err:?}"
+			);
+			last_september_first().date_naive()
+		}
+	}
+}
+
+/// the end of a half-open range that includes today (in budapest, like the school year & term ranges)
+pub fn until_today() -> NaiveDate {
+	Utc::now().with_timezone(&TIMEZONE).date_naive() + chrono::Duration::days(1)
+}
+
 pub async fn fetch_absences(client: &Client) -> anyhow::Result<Vec<AbsenceRaw>> {
-	let from = last_september_first();
+	let from = school_year_start(client).await;
 
 	let absences = client
-		.absences_range(from, Utc::now())
+		.absences_range(from..until_today())
+		.await
+		.with_context(|| format!("failed to query all absences since {}", from))?;
+
+	Ok(absences)
+}
+
+#[cfg(feature = "cache")]
+/// [fetch_absences], but only the days `cached` doesn't have (or has stale) are queried
+pub async fn fetch_absences_cached(
+	cached: &kreta_rs::cache::CachedClient<'_>,
+) -> anyhow::Result<Vec<AbsenceRaw>> {
+	let from = school_year_start(cached.client()).await;
+
+	let absences = cached
+		.absences_range(from..until_today())
 		.await
 		.with_context(|| format!("failed to query all absences since {}", from))?;
 
 	Ok(absences)
 }
 
+/// converts everything [fetch_absences] returned to the typed model \
+/// absences that fail to convert are printed and left out, like the ones [ExcuseType](crate::ExcuseType) can't be derived from
+pub fn to_model(absences: &[AbsenceRaw]) -> Vec<Absence> {
+	absences
+		.iter()
+		.filter_map(|absence| match Absence::try_from(absence.clone()) {
+			Ok(absence) => Some(absence),
+			Err(err) => {
+				eprintln!("failed to convert absence {}: {err:?}", absence.uid);
+				None
+			}
+		})
+		.collect()
+}
+
 #[cfg(feature = "save_load")]
 mod save_load {
 	use std::{io::ErrorKind, path::Path};
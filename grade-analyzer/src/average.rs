@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use kreta_rs::client::grades::{GradeKind, GradeRaw};
+
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+/// a running weighted average
+pub struct Average {
+	pub weighted_sum: f32,
+	pub weight_sum: f32,
+	pub count: i32,
+}
+impl Average {
+	pub fn push(&mut self, value: f32, weight: f32) {
+		self.weighted_sum += value * weight;
+		self.weight_sum += weight;
+		self.count += 1;
+	}
+	/// pushes the grade if it should count towards the average, returns whether it did
+	pub fn push_grade(&mut self, grade: &GradeRaw) -> bool {
+		if !counts_towards_average(grade) {
+			return false;
+		}
+		let value = match grade.numeric_value() {
+			Some(a) => a as f32,
+			None => return false,
+		};
+
+		self.push(value, grade.weight_multiplier());
+		true
+	}
+
+	/// None if there's nothing to average
+	pub fn value(&self) -> Option<f32> {
+		if self.weight_sum <= 0.0 {
+			None
+		} else {
+			Some(self.weighted_sum / self.weight_sum)
+		}
+	}
+}
+
+/// only mid-year numeric grades count, half-year and end of year grades are the result of the average, not part of it
+pub fn counts_towards_average(grade: &GradeRaw) -> bool {
+	grade.kind() == GradeKind::MidYear && grade.numeric_value().is_some() && grade.weight != Some(0)
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct SubjectAverage {
+	pub subject_name: String,
+	pub average: Average,
+}
+
+/// k: subject uid
+pub type AveragesBySubject = HashMap<String, SubjectAverage>;
+
+pub fn averages_by_subject<'a>(
+	grades: impl IntoIterator<Item = &'a GradeRaw>,
+) -> AveragesBySubject {
+	let mut buf: AveragesBySubject = HashMap::new();
+
+	for grade in grades {
+		if !counts_towards_average(grade) {
+			continue;
+		}
+
+		let entry = buf
+			.entry(grade.subject.uid.clone())
+			.or_insert_with(|| SubjectAverage {
+				subject_name: grade.subject.name.clone(),
+				average: Default::default(),
+			});
+		entry.average.push_grade(grade);
+	}
+
+	buf
+}
+
+/// the overall average is the average of the subject averages, the same way the official app calculates it
+pub fn overall_average(by_subject: &AveragesBySubject) -> Option<f32> {
+	let mut overall = Average::default();
+	for subject in by_subject.values() {
+		if let Some(avg) = subject.average.value() {
+			overall.push(avg, 1.0);
+		}
+	}
+	overall.value()
+}
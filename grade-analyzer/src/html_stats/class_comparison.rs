@@ -0,0 +1,54 @@
+use kreta_rs::client::class_groups::ClassAverageRaw;
+
+/// a table of your average vs your class' average in every subject \
+/// get from kreta_rs's Client::class_averages
+pub fn class_comparison(data: &[ClassAverageRaw]) -> String {
+	let mut sorted = data.iter().collect::<Vec<_>>();
+	sorted.sort_by(|a, b| a.subject.name.cmp(&b.subject.name));
+
+	let fmt_avg = |avg: Option<f32>| match avg {
+		Some(avg) => format!("{avg:.2}"),
+		None => "-".to_string(),
+	};
+
+	let rows = sorted.into_iter().map(|average| {
+		let name = &average.subject.name;
+		let student = fmt_avg(average.student_average);
+		let class = fmt_avg(average.class_average);
+
+		let difference = average
+			.difference
+			.or_else(|| Some(average.student_average? - average.class_average?));
+		let (difference, color) = match difference {
+			Some(diff) if diff >= 0.0 => (format!("+{diff:.2}"), "rgb(76,175,80)"),
+			Some(diff) => (format!("{diff:.2}"), "rgb(244,67,54)"),
+			None => ("-".to_string(), "var(--text-color)"),
+		};
+
+		format!(
+			"
+			<tr>
+				<td style=\"padding: 0.2rem 1rem;\">{name}</td>
+				<td style=\"padding: 0.2rem 1rem;\">{student}</td>
+				<td style=\"padding: 0.2rem 1rem;\">{class}</td>
+				<td style=\"padding: 0.2rem 1rem; font-weight: bold; color: {color};\">{difference}</td>
+			</tr>"
+		)
+	});
+	let rows = rows.collect::<String>();
+
+	format!(
+		"
+		<div style=\"display: flex; align-items: center; flex-direction: column; margin: 1rem;\">
+			<table>
+				<tr>
+					<th>Tantárgy</th>
+					<th>Átlagod</th>
+					<th>Osztályátlag</th>
+					<th>Eltérés</th>
+				</tr>
+				{rows}
+			</table>
+		</div>"
+	)
+}
@@ -0,0 +1,44 @@
+use super::*;
+use crate::{AveragesBySubject, SubjectAverage};
+
+pub fn by_subject(data: &AveragesBySubject) -> String {
+	let mut sorted = data.values().collect::<Vec<_>>();
+	sorted.sort_by(|a, b| a.subject_name.cmp(&b.subject_name));
+
+	by_subject_unsorted(sorted.iter().copied())
+}
+
+/// creates a component to be placed within an html frame
+pub fn by_subject_unsorted<'a>(data: impl Iterator<Item = &'a SubjectAverage>) -> String {
+	let cols = data.filter_map(|subject| {
+		let average = subject.average.value()?;
+
+		let col_height = average * 3.0;
+		let col_width = 3.0;
+		let col_color = hash_to_color(&subject.subject_name);
+		let col_text = std::format_args!("{average:.2}");
+		let name = &subject.subject_name;
+
+		let col = format!(
+			"
+			<div style=\"display: flex; align-items: center; flex-direction: column-reverse;\">
+				<div style=\"display: flex; justify-content: center; color: var(--text-color); margin: 0.5rem; margin-top: 0.3rem; font-size: 0.7rem; writing-mode: vertical-rl;\">
+					{name}
+				</div>
+				<div style=\"height: {col_height}em; width: {col_width}em; background-color: {col_color};\">
+				</div>
+				<div style=\"display: flex; justify-content: center; color: var(--text-color); margin: 0.2rem; font-weight: bold;\">
+					{col_text}
+				</div>
+			</div>"
+		);
+		Some(col)
+	});
+	let cols = cols.collect::<String>();
+
+	let container = format!(
+		"<div style=\"display: flex; flex-direction: row; flex-wrap: wrap; justify-content: center; align-items: flex-end;\">{cols}</div>"
+	);
+
+	container
+}
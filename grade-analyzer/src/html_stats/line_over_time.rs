@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+
+use crate::over_time::AveragePoint;
+
+/// get from crate::over_time::average_over_time
+pub fn line_over_time(data: &[AveragePoint]) -> String {
+	let (first, last) = match (data.first(), data.last()) {
+		(Some(first), Some(last)) => (first.date, last.date),
+		_ => return String::new(),
+	};
+	let total_seconds = (last - first).num_seconds().max(1) as f32;
+
+	// svg
+	let view_width = 100.0;
+	let view_height = 50.0;
+	let view_box = "0 0 100 50";
+
+	let date_to_x =
+		|date: DateTime<Utc>| ((date - first).num_seconds() as f32 / total_seconds) * view_width;
+	// grades go from 1 to 5
+	let average_to_y = |average: f32| view_height - ((average - 1.0) / 4.0) * view_height;
+
+	let points = data
+		.iter()
+		.map(|point| format!("{},{}", date_to_x(point.date), average_to_y(point.average)))
+		.collect::<Vec<String>>()
+		.join(" ");
+
+	let fill = "none";
+	let color = "rgb(66,135,245)";
+	let width = 0.5;
+	let data_points = format!(
+		"<polyline fill=\"{fill}\" stroke=\"{color}\" stroke-width=\"{width}\" points=\"{points}\" />"
+	);
+
+	let undescriptive_gray = "rgb(107,107,107)";
+	let guidelines = (1..=5)
+		.map(|grade| {
+			let y = average_to_y(grade as f32);
+
+			let fill = "none";
+			let color = undescriptive_gray;
+			let width = 0.06;
+			let points = format!("0,{y} {view_width},{y}");
+
+			format!(
+				"<polyline fill=\"{fill}\" stroke=\"{color}\" stroke-width=\"{width}\" points=\"{points}\" />"
+			)
+		})
+		.collect::<String>();
+
+	let svg = format!(
+		"
+		<div style=\"display: flex; align-items: center; flex-direction: column; margin: 1rem;\">
+			<svg viewBox=\"{view_box}\" style=\"width: 80rem; max-width: 100%; max-height: 100%; overflow: visible;\">
+				{guidelines}
+				{data_points}
+			</svg>
+		</div>
+		"
+	);
+
+	svg
+}
@@ -0,0 +1,12 @@
+pub use absence_analyzer::html_stats::{Color, hash_to_color, profile_header};
+
+mod col_by_subject;
+pub use col_by_subject::*;
+mod total;
+pub use total::*;
+mod line_over_time;
+pub use line_over_time::*;
+mod class_comparison;
+pub use class_comparison::*;
+mod needed;
+pub use needed::*;
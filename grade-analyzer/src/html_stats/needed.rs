@@ -0,0 +1,55 @@
+use crate::{AveragesBySubject, NeededGrade, solver::needed_grade_for};
+
+/// the next x.5 an average has to reach to round up to a better grade
+fn next_threshold(average: f32) -> Option<f32> {
+	let threshold = (average + 0.5).floor() + 0.5;
+	if threshold > 5.0 {
+		None
+	} else {
+		Some(threshold)
+	}
+}
+
+/// for every subject, shows what grade you'd need (with normal weight) to round up to the next grade
+pub fn needed_table(data: &AveragesBySubject) -> String {
+	let mut sorted = data.values().collect::<Vec<_>>();
+	sorted.sort_by(|a, b| a.subject_name.cmp(&b.subject_name));
+
+	let rows = sorted.into_iter().filter_map(|subject| {
+		let average = subject.average.value()?;
+		let target = next_threshold(average)?;
+
+		let needed = match needed_grade_for(&subject.average, 1.0, target) {
+			NeededGrade::Grade(grade) => format!("{grade}"),
+			NeededGrade::Impossible { .. } => "egy jegyből nem megy".to_string(),
+		};
+		let name = &subject.subject_name;
+
+		let row = format!(
+			"
+			<tr>
+				<td style=\"padding: 0.2rem 1rem;\">{name}</td>
+				<td style=\"padding: 0.2rem 1rem;\">{average:.2}</td>
+				<td style=\"padding: 0.2rem 1rem;\">{target:.1}</td>
+				<td style=\"padding: 0.2rem 1rem; font-weight: bold;\">{needed}</td>
+			</tr>"
+		);
+		Some(row)
+	});
+	let rows = rows.collect::<String>();
+
+	format!(
+		"
+		<div style=\"display: flex; align-items: center; flex-direction: column; margin: 1rem;\">
+			<table>
+				<tr>
+					<th>Tantárgy</th>
+					<th>Átlag</th>
+					<th>Cél</th>
+					<th>Kellő jegy</th>
+				</tr>
+				{rows}
+			</table>
+		</div>"
+	)
+}
@@ -0,0 +1,78 @@
+use kreta_rs::client::{
+	class_groups::ClassAverageRaw, grades::GradeRaw, profile::StudentProfileRaw,
+};
+
+use crate::{AveragesBySubject, averages_by_subject, over_time::average_over_time};
+
+pub fn html_stats_content(iter: &[GradeRaw]) -> String {
+	let by_subject = averages_by_subject(iter);
+
+	let graph = super::by_subject(&by_subject);
+	let overall = overall_html(&by_subject);
+	let needed = super::needed_table(&by_subject);
+
+	let line_graph = {
+		let over_time = average_over_time(iter);
+		let line_graph = over_time.map(|a| super::line_over_time(&a));
+
+		line_graph.unwrap_or_else(|err| {
+			format!(
+				"could not create line graph due to an error while sorting grades by date:\n{err}"
+			)
+		})
+	};
+
+	format!(
+		"
+		\n\n{overall}
+		<div style=\"font-size: 0.85rem;\">
+			{graph}
+		</div>
+		\n\n{line_graph}
+		\n\n{needed}"
+	)
+}
+
+const FRAME: &str = include_str!("./frame.html");
+pub fn html_stats(data: &[GradeRaw]) -> String {
+	let content = html_stats_content(data);
+	FRAME.replace("{content}", &content)
+}
+/// [html_stats] with the student's name, class and school on top,
+/// and a comparison with the class' averages if there are any
+pub fn html_stats_with_profile(
+	data: &[GradeRaw],
+	profile: Option<&StudentProfileRaw>,
+	class_name: Option<&str>,
+	class_averages: &[ClassAverageRaw],
+) -> String {
+	let header = profile
+		.map(|profile| super::profile_header(profile, class_name))
+		.unwrap_or_default();
+	let content = html_stats_content(data);
+	let comparison = if class_averages.is_empty() {
+		String::new()
+	} else {
+		super::class_comparison(class_averages)
+	};
+	FRAME.replace("{content}", &format!("{header}{content}\n\n{comparison}"))
+}
+
+pub fn overall_html(data: &AveragesBySubject) -> String {
+	let overall = crate::overall_average(data);
+
+	let body = match overall {
+		None => "Még nincs jegyed.".to_string(),
+		Some(overall) => {
+			format!("Az átlagod: <span style=\"font-weight: bold;\">{overall:.2}</span>")
+		}
+	};
+	format!(
+		"
+		<div style=\"display: flex; align-items: center; flex-direction: column; margin: 1rem;\">
+			<div style=\"margin: 1rem;\">
+				{body}
+			</div>
+		</div>"
+	)
+}
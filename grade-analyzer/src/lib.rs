@@ -0,0 +1,11 @@
+pub mod average;
+pub mod html_stats;
+pub mod over_time;
+pub mod retreive;
+pub mod solver;
+
+pub use average::*;
+pub use html_stats::html_stats;
+pub use over_time::{average_over_time, subject_average_over_time};
+pub use retreive::fetch_grades;
+pub use solver::{NeededGrade, needed_grade};
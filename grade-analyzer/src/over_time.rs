@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use kreta_rs::client::grades::GradeRaw;
+
+use crate::{Average, counts_towards_average};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AveragePoint {
+	/// the date of the grade that changed the average
+	pub date: DateTime<Utc>,
+	pub average: f32,
+}
+
+/// returns the grades that count towards the average, sorted by date
+fn sorted_by_date<'a>(
+	grades: impl IntoIterator<Item = &'a GradeRaw>,
+) -> anyhow::Result<Vec<(DateTime<Utc>, &'a GradeRaw)>> {
+	let iter = grades
+		.into_iter()
+		.filter(|grade| counts_towards_average(grade))
+		.map(|grade| {
+			let date: DateTime<Utc> = grade
+				.date
+				.parse()
+				.with_context(|| format!("failed to parse {} as DateTime<Utc>", grade.date))?;
+			anyhow::Ok((date, grade))
+		});
+	let mut buf = iter.collect::<anyhow::Result<Vec<_>>>()?;
+	buf.sort_by_key(|(date, _)| *date);
+
+	Ok(buf)
+}
+
+/// the overall average (see [crate::overall_average]) after each grade
+pub fn average_over_time<'a>(
+	grades: impl IntoIterator<Item = &'a GradeRaw>,
+) -> anyhow::Result<Vec<AveragePoint>> {
+	let sorted = sorted_by_date(grades)?;
+
+	let mut by_subject: HashMap<&str, Average> = HashMap::new();
+	let mut buf = Vec::with_capacity(sorted.len());
+	for (date, grade) in sorted {
+		by_subject
+			.entry(&grade.subject.uid)
+			.or_default()
+			.push_grade(grade);
+
+		let mut overall = Average::default();
+		for avg in by_subject.values().filter_map(Average::value) {
+			overall.push(avg, 1.0);
+		}
+		if let Some(average) = overall.value() {
+			buf.push(AveragePoint { date, average });
+		}
+	}
+
+	Ok(buf)
+}
+
+/// the average of a single subject after each grade in that subject
+pub fn subject_average_over_time<'a>(
+	grades: impl IntoIterator<Item = &'a GradeRaw>,
+	subject_uid: &str,
+) -> anyhow::Result<Vec<AveragePoint>> {
+	let sorted = sorted_by_date(
+		grades
+			.into_iter()
+			.filter(|grade| grade.subject.uid == subject_uid),
+	)?;
+
+	let mut avg = Average::default();
+	let mut buf = Vec::with_capacity(sorted.len());
+	for (date, grade) in sorted {
+		avg.push_grade(grade);
+		if let Some(average) = avg.value() {
+			buf.push(AveragePoint { date, average });
+		}
+	}
+
+	Ok(buf)
+}
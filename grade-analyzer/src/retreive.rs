@@ -0,0 +1,14 @@
+use anyhow::Context;
+use kreta_rs::client::{Client, grades::GradeRaw};
+
+pub async fn fetch_grades(client: &Client) -> anyhow::Result<Vec<GradeRaw>> {
+	let from = absence_analyzer::retreive::school_year_start(client).await;
+	let to = absence_analyzer::retreive::until_today();
+
+	let grades = client
+		.grades_range(from..to)
+		.await
+		.with_context(|| format!("failed to query all grades since {}", from))?;
+
+	Ok(grades)
+}
@@ -0,0 +1,107 @@
+use kreta_rs::client::grades::GradeRaw;
+
+use crate::Average;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NeededGrade {
+	/// the lowest grade that gets you to the target
+	Grade(i32),
+	/// not even a 5 is enough, `with_best` is the average you'd have with a 5
+	Impossible { with_best: f32 },
+}
+
+/// averages are f32s, an average of exactly 3.5 can come out as 3.4999998
+const EPSILON: f32 = 1e-4;
+
+/// answers "what grade with weight `weight` do i need to reach an average of `target`" \
+/// weight is a multiplier, so 1.0 is a normal grade and 2.0 is a double weight one
+pub fn needed_grade_for(average: &Average, weight: f32, target: f32) -> NeededGrade {
+	for grade in 1..=5 {
+		let mut with_grade = *average;
+		with_grade.push(grade as f32, weight);
+
+		if with_grade.value().is_some_and(|a| a >= target - EPSILON) {
+			return NeededGrade::Grade(grade);
+		}
+	}
+
+	let mut with_best = *average;
+	with_best.push(5.0, weight);
+	NeededGrade::Impossible {
+		with_best: with_best.value().unwrap_or_default(),
+	}
+}
+
+/// [needed_grade_for] for a given subject out of all your grades
+pub fn needed_grade<'a>(
+	grades: impl IntoIterator<Item = &'a GradeRaw>,
+	subject_uid: &str,
+	weight: f32,
+	target: f32,
+) -> NeededGrade {
+	let mut average = Average::default();
+	for grade in grades
+		.into_iter()
+		.filter(|grade| grade.subject.uid == subject_uid)
+	{
+		average.push_grade(grade);
+	}
+
+	needed_grade_for(&average, weight, target)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_needed_grade() {
+		let mut average = Average::default();
+		average.push(4.0, 1.0);
+		average.push(3.0, 1.0);
+
+		// (4 + 3 + 5) / 3 = 4.0
+		assert_eq!(needed_grade_for(&average, 1.0, 4.0), NeededGrade::Grade(5));
+		// (4 + 3 + 4 * 2) / 4 = 3.75
+		assert_eq!(needed_grade_for(&average, 2.0, 3.75), NeededGrade::Grade(4));
+		assert_eq!(needed_grade_for(&average, 1.0, 1.0), NeededGrade::Grade(1));
+		assert_eq!(
+			needed_grade_for(&average, 1.0, 4.5),
+			NeededGrade::Impossible { with_best: 4.0 }
+		);
+
+		let empty = Average::default();
+		assert_eq!(needed_grade_for(&empty, 1.0, 3.5), NeededGrade::Grade(4));
+	}
+
+	#[test]
+	fn test_needed_grade_exact_boundary() {
+		// a 1 with 20% weight: (1 * 0.2 + 4) / 1.2 is exactly 3.5, but 3.4999998 in f32
+		let mut average = Average::default();
+		average.push(1.0, 0.2);
+		assert_eq!(needed_grade_for(&average, 1.0, 3.5), NeededGrade::Grade(4));
+
+		// (2 * 0.6 + 4) / 1.6 = 3.25
+		let mut average = Average::default();
+		average.push(2.0, 0.6);
+		assert_eq!(needed_grade_for(&average, 1.0, 3.25), NeededGrade::Grade(4));
+	}
+
+	#[test]
+	fn test_needed_grade_unreachable() {
+		let mut average = Average::default();
+		average.push(1.0, 2.0);
+		average.push(2.0, 1.0);
+
+		// (1 * 2 + 2 + 5) / 4 = 2.25
+		assert_eq!(
+			needed_grade_for(&average, 1.0, 2.5),
+			NeededGrade::Impossible { with_best: 2.25 }
+		);
+		// just above what a 5 gets you
+		assert!(matches!(
+			needed_grade_for(&average, 1.0, 2.251),
+			NeededGrade::Impossible { .. }
+		));
+	}
+}
@@ -4,14 +4,17 @@ use std::{
 };
 
 use anyhow::Context;
-use chrono::{DateTime, Utc};
-use chrono_tz::Europe::Budapest;
-use kreta_rs::client::{
-	Client, absences::AbsenceRaw, exam::ExamRaw, homework::HomeworkRaw, timetable::LessonRaw,
+use chrono::DateTime;
+use chrono_tz::Tz;
+use kreta_rs::{
+	client::{
+		Client, absences::AbsenceRaw, exam::ExamRaw, homework::HomeworkRaw, timetable::LessonRaw,
+	},
+	model::{Absence, Exam, Homework, Lesson},
 };
 
-fn get_homework_hash(date: &DateTime<Utc>, subject_uid: &str) -> anyhow::Result<u64> {
-	let deadline_date = date.with_timezone(&Budapest).date_naive();
+fn get_homework_hash(date: &DateTime<Tz>, subject_uid: &str) -> anyhow::Result<u64> {
+	let deadline_date = date.date_naive();
 
 	let mut hasher = DefaultHasher::new();
 	deadline_date.hash(&mut hasher);
@@ -22,10 +25,10 @@ fn get_homework_hash(date: &DateTime<Utc>, subject_uid: &str) -> anyhow::Result<
 }
 
 pub type Preprocessed = (
-	Vec<LessonRaw>,
-	HashMap<u64, HomeworkRaw>,
-	HashMap<String, ExamRaw>,
-	HashMap<u64, AbsenceRaw>,
+	Vec<Lesson>,
+	HashMap<u64, Homework>,
+	HashMap<String, Exam>,
+	HashMap<u64, Absence>,
 );
 
 /// simple, 3 weeks at max
@@ -35,8 +38,11 @@ pub async fn get_preprocessed(
 	to: &str,
 ) -> anyhow::Result<Preprocessed> {
 	let timetable = async {
-		let timetable = client.timetable(from, to).await;
-		timetable
+		let timetable_raw = client.timetable(from, to).await?;
+
+		let mut timetable = Vec::new();
+		process_timetable(&mut timetable, timetable_raw)?;
+		anyhow::Ok(timetable)
 	};
 	let homework = async {
 		let homework_raw = client.homework(from, to).await?;
@@ -49,7 +55,7 @@ pub async fn get_preprocessed(
 		let exams_raw = client.exams(from, to).await?;
 
 		let mut exams_map = HashMap::new();
-		process_exams(&mut exams_map, exams_raw);
+		process_exams(&mut exams_map, exams_raw)?;
 		anyhow::Ok(exams_map)
 	};
 	let absences = async {
@@ -72,25 +78,28 @@ pub async fn get_preprocessed(
 }
 
 #[cfg(feature = "timerange")]
+/// `range` is half-open, like the *_range functions of kreta-rs
 pub async fn get_preprocessed_range(
 	client: &Client,
-	from: chrono::DateTime<chrono::Utc>,
-	to: chrono::DateTime<chrono::Utc>,
+	range: std::ops::Range<chrono::NaiveDate>,
 ) -> anyhow::Result<Preprocessed> {
 	use futures::StreamExt;
 	let timetable = async {
 		let mut buf = Vec::new();
-		let mut stream = client.timetable_range_stream(from.clone(), to.clone());
+		let mut stream = client.timetable_range_stream(range.clone());
 		while let Some(next) = stream.next().await {
 			let next = next.with_context(|| "while reading chunk from timetable_range_stream")?;
-			process_timetable(&mut buf, next);
+			process_timetable(&mut buf, next)
+				.with_context(|| "while processing chunk from timetable_range_stream")?;
 		}
+		let mut seen = std::collections::HashSet::new();
+		buf.retain(|lesson| seen.insert(lesson.uid.clone()));
 
 		anyhow::Ok(buf)
 	};
 	let homework = async {
 		let mut buf = HashMap::new();
-		let mut stream = client.homework_range_stream(from.clone(), to.clone());
+		let mut stream = client.homework_range_stream(range.clone());
 		while let Some(next) = stream.next().await {
 			let next = next.with_context(|| "while reading chunk from homework_range_stream")?;
 			process_homework(&mut buf, next)
@@ -101,17 +110,18 @@ pub async fn get_preprocessed_range(
 	};
 	let exams = async {
 		let mut buf = HashMap::new();
-		let mut stream = client.exams_range_stream(from.clone(), to.clone());
+		let mut stream = client.exams_range_stream(range.clone());
 		while let Some(next) = stream.next().await {
 			let next = next.with_context(|| "while reading chunk from exams_range_stream")?;
-			process_exams(&mut buf, next);
+			process_exams(&mut buf, next)
+				.with_context(|| "while processing chunk from exams_range_stream")?;
 		}
 
 		anyhow::Ok(buf)
 	};
 	let absences = async {
 		let mut buf = HashMap::new();
-		let mut stream = client.absences_range_stream(from.clone(), to.clone());
+		let mut stream = client.absences_range_stream(range.clone());
 		while let Some(next) = stream.next().await {
 			let next = next.with_context(|| "while reading chunk from absences_range_stream")?;
 			process_absences(&mut buf, next)
@@ -122,33 +132,78 @@ pub async fn get_preprocessed_range(
 
 	let (timetable, homework, exams, absences) = tokio::join!(timetable, homework, exams, absences);
 	let (timetable, homework, exams, absences) = (
-		timetable.with_context(|| format!("while querying lessons between {from:?} and {to:?}"))?,
-		homework.with_context(|| format!("while querying homework between {from:?} and {to:?}"))?,
-		exams.with_context(|| format!("while querying exams between {from:?} and {to:?}"))?,
-		absences.with_context(|| format!("while querying absences between {from:?} and {to:?}"))?,
+		timetable.with_context(|| format!("while querying lessons in {range:?}"))?,
+		homework.with_context(|| format!("while querying homework in {range:?}"))?,
+		exams.with_context(|| format!("while querying exams in {range:?}"))?,
+		absences.with_context(|| format!("while querying absences in {range:?}"))?,
 	);
 
 	anyhow::Ok((timetable, homework, exams, absences))
 }
 
-fn process_timetable(buf: &mut Vec<LessonRaw>, incoming: impl IntoIterator<Item = LessonRaw>) {
-	buf.extend(incoming);
+#[cfg(feature = "cache")]
+/// [get_preprocessed_range], but only the days `cached` doesn't have (or has stale) are queried
+pub async fn get_preprocessed_cached(
+	cached: &kreta_rs::cache::CachedClient<'_>,
+	range: std::ops::Range<chrono::NaiveDate>,
+) -> anyhow::Result<Preprocessed> {
+	let (timetable, homework, exams, absences) = tokio::join!(
+		cached.timetable_range(range.clone()),
+		cached.homework_range(range.clone()),
+		cached.exams_range(range.clone()),
+		cached.absences_range(range.clone()),
+	);
+	preprocess(
+		timetable.with_context(|| format!("while querying lessons in {range:?}"))?,
+		homework.with_context(|| format!("while querying homework in {range:?}"))?,
+		exams.with_context(|| format!("while querying exams in {range:?}"))?,
+		absences.with_context(|| format!("while querying absences in {range:?}"))?,
+	)
+}
+
+/// what the get_preprocessed functions do with what they queried, for when they're queried some other way
+pub fn preprocess(
+	timetable: Vec<LessonRaw>,
+	homework: Vec<HomeworkRaw>,
+	exams: Vec<ExamRaw>,
+	absences: Vec<AbsenceRaw>,
+) -> anyhow::Result<Preprocessed> {
+	let mut lessons = Vec::new();
+	process_timetable(&mut lessons, timetable)?;
+	let mut homework_map = HashMap::new();
+	process_homework(&mut homework_map, homework)?;
+	let mut exams_map = HashMap::new();
+	process_exams(&mut exams_map, exams)?;
+	let mut absences_map = HashMap::new();
+	process_absences(&mut absences_map, absences)?;
+
+	Ok((lessons, homework_map, exams_map, absences_map))
+}
+
+fn process_timetable(
+	buf: &mut Vec<Lesson>,
+	incoming: impl IntoIterator<Item = LessonRaw>,
+) -> anyhow::Result<()> {
+	let iter = incoming.into_iter().map(|lesson| {
+		let uid = lesson.uid.clone();
+		Lesson::try_from(lesson).with_context(|| format!("while converting lesson {uid}"))
+	});
+	let iter = iter.collect::<anyhow::Result<Vec<_>>>()?;
+	buf.extend(iter);
+	Ok(())
 }
 /// order all the homework we got into a hashmap where:
 /// - key is deadline date (without time) & subject name hasher
-/// - value is the HomeworkRaw
+/// - value is the Homework
 fn process_homework(
-	buf: &mut HashMap<u64, HomeworkRaw>,
+	buf: &mut HashMap<u64, Homework>,
 	incoming: impl IntoIterator<Item = HomeworkRaw>,
 ) -> anyhow::Result<()> {
 	let iter = incoming.into_iter().map(|hw| {
-		let deadline_date: DateTime<Utc> = hw.date_deadline.parse().with_context(|| {
-			format!(
-				"failed to parse homework deadline date {} as DateTime<Utc>",
-				hw.date_deadline
-			)
-		})?;
-		let hash = get_homework_hash(&deadline_date, &hw.subject.uid).with_context(|| {
+		let uid = hw.uid.clone();
+		let hw =
+			Homework::try_from(hw).with_context(|| format!("while converting homework {uid}"))?;
+		let hash = get_homework_hash(&hw.date_deadline, &hw.subject.uid).with_context(|| {
 			format!(
 				"while calculating hash for {} homework due {}",
 				hw.subject_name, hw.date_deadline
@@ -160,28 +215,35 @@ fn process_homework(
 	buf.extend(iter);
 	Ok(())
 }
-fn process_exams(buf: &mut HashMap<String, ExamRaw>, incoming: impl IntoIterator<Item = ExamRaw>) {
-	let iter = incoming.into_iter().map(|exam| (exam.uid.clone(), exam));
+fn process_exams(
+	buf: &mut HashMap<String, Exam>,
+	incoming: impl IntoIterator<Item = ExamRaw>,
+) -> anyhow::Result<()> {
+	let iter = incoming.into_iter().map(|exam| {
+		let uid = exam.uid.clone();
+		let exam = Exam::try_from(exam).with_context(|| format!("while converting exam {uid}"))?;
+		Ok((uid, exam))
+	});
+	let iter = iter.collect::<anyhow::Result<Vec<_>>>()?;
 	buf.extend(iter);
+	Ok(())
 }
 /// same logic as homework since no persistent id
 fn process_absences(
-	buf: &mut HashMap<u64, AbsenceRaw>,
+	buf: &mut HashMap<u64, Absence>,
 	incoming: impl IntoIterator<Item = AbsenceRaw>,
 ) -> anyhow::Result<()> {
 	let iter = incoming.into_iter().map(|absence| {
-		let date: chrono::DateTime<Utc> = absence.lesson.start_time.parse().with_context(|| {
-			format!(
-				"failed to parse absence lesson start time {} as DateTime<Utc>",
-				absence.lesson.start_time
-			)
-		})?;
-		let hash = get_homework_hash(&date, &absence.subject.uid).with_context(|| {
-			format!(
-				"while calculating hash for {} absence from {}",
-				absence.subject.name, absence.lesson.start_time
-			)
-		})?;
+		let uid = absence.uid.clone();
+		let absence = Absence::try_from(absence)
+			.with_context(|| format!("while converting absence {uid}"))?;
+		let hash =
+			get_homework_hash(&absence.lesson_start, &absence.subject.uid).with_context(|| {
+				format!(
+					"while calculating hash for {} absence from {}",
+					absence.subject.name, absence.lesson_start
+				)
+			})?;
 		Ok((hash, absence))
 	});
 	let iter = iter.collect::<anyhow::Result<Vec<_>>>()?;
@@ -193,19 +255,19 @@ type CombinedTimetable = Vec<CombinedLesson>;
 
 #[derive(Clone, Debug)]
 pub struct CombinedLesson {
-	pub lesson_raw: LessonRaw,
-	pub exam: Option<ExamRaw>,
-	pub homework: Option<HomeworkRaw>,
-	pub absence: Option<AbsenceRaw>,
+	pub lesson: Lesson,
+	pub exam: Option<Exam>,
+	pub homework: Option<Homework>,
+	pub absence: Option<Absence>,
 }
 
 fn match_preprocessed_internal(
 	preprocessed: Preprocessed,
 ) -> anyhow::Result<(
 	CombinedTimetable,
-	HashMap<u64, HomeworkRaw>,
-	HashMap<String, ExamRaw>,
-	HashMap<u64, AbsenceRaw>,
+	HashMap<u64, Homework>,
+	HashMap<String, Exam>,
+	HashMap<u64, Absence>,
 )> {
 	let (timetable, mut homework, mut exams, mut absences) = preprocessed;
 	let mut combined = Vec::with_capacity(timetable.len());
@@ -213,14 +275,7 @@ fn match_preprocessed_internal(
 	for lesson in timetable {
 		let (homework, absence) = match &lesson.subject {
 			Some(subject) => {
-				let date: DateTime<Utc> = lesson.start_time.parse().with_context(|| {
-					format!(
-						"while parsing lesson start time {} as a DateTime",
-						lesson.start_time
-					)
-				})?;
-
-				let homework_hash = get_homework_hash(&date, &subject.uid)?;
+				let homework_hash = get_homework_hash(&lesson.start, &subject.uid)?;
 
 				let homework = homework.remove(&homework_hash);
 				let absence = absences.remove(&homework_hash);
@@ -235,7 +290,7 @@ fn match_preprocessed_internal(
 		};
 
 		combined.push(CombinedLesson {
-			lesson_raw: lesson,
+			lesson,
 			exam,
 			homework,
 			absence,
@@ -253,8 +308,8 @@ pub fn match_preprocessed_with_remainder(
 	preprocessed: Preprocessed,
 ) -> anyhow::Result<(
 	CombinedTimetable,
-	HashMap<u64, HomeworkRaw>,
-	HashMap<String, ExamRaw>,
+	HashMap<u64, Homework>,
+	HashMap<String, Exam>,
 )> {
 	let (timetable, mut homework, mut exams, mut absences) =
 		match_preprocessed_internal(preprocessed)?;
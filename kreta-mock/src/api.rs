@@ -0,0 +1,184 @@
+//! https://[instituteCode].e-kreta.hu/ellenorzo/v3, served from /kreta-mock/fixtures \
+//! the filtering follows what kreta does at the edges of a range, not what would make sense
+
+use std::time::Instant;
+
+use actix_web::{
+	HttpRequest, HttpResponse, get,
+	http::{StatusCode, header},
+	web,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+
+use crate::State;
+
+struct Endpoint {
+	name: &'static str,
+	fixture: &'static str,
+	/// the field the range is matched against \
+	/// None for endpoints that don't take a range, their fixture is returned as is
+	date_field: Option<&'static str>,
+	/// the longest range kreta accepts, in days
+	max_days: i64,
+	/// OrarendElemek doesn't return anything on datumIg, everything else does
+	exclusive_end: bool,
+}
+
+const ENDPOINTS: &[Endpoint] = &[
+	Endpoint {
+		name: "OrarendElemek",
+		fixture: include_str!("../fixtures/timetable.json"),
+		date_field: Some("Datum"),
+		max_days: 31,
+		exclusive_end: true,
+	},
+	Endpoint {
+		name: "HaziFeladatok",
+		fixture: include_str!("../fixtures/homework.json"),
+		date_field: Some("HataridoDatuma"),
+		max_days: 21,
+		exclusive_end: false,
+	},
+	Endpoint {
+		name: "BejelentettSzamonkeresek",
+		fixture: include_str!("../fixtures/exams.json"),
+		date_field: Some("Datum"),
+		max_days: 31,
+		exclusive_end: false,
+	},
+	Endpoint {
+		name: "Mulasztasok",
+		fixture: include_str!("../fixtures/absences.json"),
+		date_field: Some("Datum"),
+		max_days: 21,
+		exclusive_end: false,
+	},
+	Endpoint {
+		name: "TanuloAdatlap",
+		fixture: include_str!("../fixtures/profile.json"),
+		date_field: None,
+		max_days: 0,
+		exclusive_end: false,
+	},
+];
+
+#[derive(Deserialize)]
+struct RangeQuery {
+	#[serde(rename = "datumTol")]
+	from: Option<String>,
+	#[serde(rename = "datumIg")]
+	to: Option<String>,
+}
+
+#[get("/{inst_id}/ellenorzo/v3/{sajat}/{endpoint}")]
+async fn ellenorzo(
+	state: web::Data<State>,
+	path: web::Path<(String, String, String)>,
+	query: web::Query<RangeQuery>,
+	req: HttpRequest,
+) -> HttpResponse {
+	let (inst_id, sajat, name) = path.into_inner();
+	// homework is under /Sajat, everything else under /sajat
+	if inst_id != state.config.inst_id || !sajat.eq_ignore_ascii_case("sajat") {
+		return HttpResponse::NotFound().finish();
+	}
+	let Some(endpoint) = ENDPOINTS.iter().find(|endpoint| endpoint.name == name) else {
+		return HttpResponse::NotFound().finish();
+	};
+
+	{
+		let mut inner = state.lock();
+		let token = req
+			.headers()
+			.get(header::AUTHORIZATION)
+			.and_then(|val| val.to_str().ok())
+			.and_then(|val| val.strip_prefix("Bearer "));
+		let authorized = token
+			.and_then(|token| inner.access_tokens.get(token))
+			.is_some_and(|expires| *expires > Instant::now());
+		if !authorized {
+			return HttpResponse::Unauthorized().finish();
+		}
+		*inner.requests.entry(name.clone()).or_default() += 1;
+
+		if let Some(status) = inner.failures.pop_front() {
+			let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+			return HttpResponse::build(status).body("mock failure");
+		}
+		inner.in_flight += 1;
+		inner.max_in_flight = inner.max_in_flight.max(inner.in_flight);
+	}
+
+	let resp = respond(endpoint, &query);
+	let delay = state.config.response_delay;
+	if !delay.is_zero() {
+		actix_web::rt::time::sleep(delay).await;
+	}
+	state.lock().in_flight -= 1;
+
+	resp
+}
+
+fn respond(endpoint: &Endpoint, query: &RangeQuery) -> HttpResponse {
+	let Some(date_field) = endpoint.date_field else {
+		return HttpResponse::Ok()
+			.content_type("application/json")
+			.body(endpoint.fixture);
+	};
+	let (Some(from), Some(to)) = (parse_date(&query.from), parse_date(&query.to)) else {
+		return bad_request("datumTol and datumIg are required (yyyy-mm-dd)");
+	};
+	if from > to {
+		return bad_request("datumTol is after datumIg");
+	}
+	if (to - from).num_days() > endpoint.max_days {
+		return bad_request("the requested range is too long");
+	}
+
+	match filter(endpoint, date_field, from, to) {
+		Ok(items) => HttpResponse::Ok().json(items),
+		Err(err) => HttpResponse::InternalServerError().body(format!("{err:?}")),
+	}
+}
+
+fn filter(
+	endpoint: &Endpoint,
+	date_field: &str,
+	from: NaiveDate,
+	to: NaiveDate,
+) -> anyhow::Result<Vec<serde_json::Value>> {
+	let items: Vec<serde_json::Value> = serde_json::from_str(endpoint.fixture)?;
+
+	let mut buf = Vec::new();
+	for item in items {
+		let date = item
+			.get(date_field)
+			.and_then(|date| date.as_str())
+			.ok_or_else(|| anyhow::anyhow!("fixture of {} has no {date_field}", endpoint.name))?;
+		// kreta stores midnight in budapest as 23:00 the day before in utc
+		let date = DateTime::parse_from_rfc3339(date)?
+			.with_timezone(&Utc)
+			.with_timezone(&chrono_tz::Europe::Budapest)
+			.date_naive();
+
+		let in_range = if endpoint.exclusive_end {
+			from <= date && date < to
+		} else {
+			from <= date && date <= to
+		};
+		if in_range {
+			buf.push(item);
+		}
+	}
+
+	Ok(buf)
+}
+
+fn parse_date(date: &Option<String>) -> Option<NaiveDate> {
+	NaiveDate::parse_from_str(date.as_deref()?, "%Y-%m-%d").ok()
+}
+
+fn bad_request(message: &str) -> HttpResponse {
+	HttpResponse::BadRequest().json(serde_json::json!({ "Message": message }))
+}
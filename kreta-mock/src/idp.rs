@@ -0,0 +1,304 @@
+//! https://idp.e-kreta.hu, see /thanks.py for how the real one behaves
+
+use std::time::Instant;
+
+use actix_web::{HttpResponse, get, http::header, post, web};
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use kreta_rs::login::login_flow::CLIENT_ID;
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+
+use crate::State;
+
+/// an authorize request waiting for the user to log in
+pub(crate) struct Pending {
+	redirect_uri: String,
+	state: String,
+	code_challenge: String,
+	verification_token: Option<String>,
+	authenticated: bool,
+}
+
+/// what an authorization code can be exchanged for
+pub(crate) struct Grant {
+	redirect_uri: String,
+	code_challenge: String,
+}
+
+#[derive(Deserialize)]
+struct AuthorizeQuery {
+	redirect_uri: String,
+	client_id: String,
+	state: String,
+	code_challenge: String,
+	code_challenge_method: String,
+}
+
+#[get("/connect/authorize")]
+async fn authorize(state: web::Data<State>, query: web::Query<AuthorizeQuery>) -> HttpResponse {
+	let query = query.into_inner();
+	if query.client_id != CLIENT_ID || query.code_challenge_method != "S256" {
+		return HttpResponse::BadRequest().body("invalid_request");
+	}
+
+	let mut inner = state.lock();
+	let id = inner.next_id("authorize");
+	inner.pending.insert(
+		id.clone(),
+		Pending {
+			redirect_uri: query.redirect_uri,
+			state: query.state,
+			code_challenge: query.code_challenge,
+			verification_token: None,
+			authenticated: false,
+		},
+	);
+
+	redirect_to_login(&id)
+}
+
+#[derive(Deserialize)]
+struct LoginPageQuery {
+	#[serde(rename = "ReturnUrl")]
+	return_url: String,
+}
+
+#[get("/account/login")]
+async fn login_page(state: web::Data<State>, query: web::Query<LoginPageQuery>) -> HttpResponse {
+	let mut inner = state.lock();
+	let Some(id) = pending_id(&query.return_url) else {
+		return HttpResponse::BadRequest().body("invalid ReturnUrl");
+	};
+	let token = inner.next_id("csrf");
+	let Some(pending) = inner.pending.get_mut(id) else {
+		return HttpResponse::BadRequest().body("unknown ReturnUrl");
+	};
+	pending.verification_token = Some(token.clone());
+
+	login_html(&query.return_url, &token, false)
+}
+
+#[derive(Deserialize)]
+struct LoginForm {
+	#[serde(rename = "ReturnUrl")]
+	return_url: String,
+	#[serde(rename = "__RequestVerificationToken")]
+	verification_token: String,
+	#[serde(rename = "UserName")]
+	username: String,
+	#[serde(rename = "Password")]
+	passwd: String,
+	#[serde(rename = "InstituteCode")]
+	inst_id: String,
+}
+
+#[post("/account/login")]
+async fn login(state: web::Data<State>, form: web::Form<LoginForm>) -> HttpResponse {
+	let config = &state.config;
+	let valid_credentials = form.username == config.username
+		&& form.passwd == config.passwd
+		&& form.inst_id == config.inst_id;
+
+	let mut inner = state.lock();
+	let token = inner.next_id("csrf");
+	let Some(pending) = pending_id(&form.return_url).and_then(|id| inner.pending.get_mut(id))
+	else {
+		return HttpResponse::BadRequest().body("unknown ReturnUrl");
+	};
+	if pending.verification_token.as_deref() != Some(form.verification_token.as_str()) {
+		return HttpResponse::BadRequest().body("invalid __RequestVerificationToken");
+	}
+
+	// just like kreta: a wrong password is a 200 with the login page and an error message on it
+	if !valid_credentials {
+		pending.verification_token = Some(token.clone());
+		return login_html(&form.return_url, &token, true);
+	}
+	pending.authenticated = true;
+
+	HttpResponse::Ok().body("logged in")
+}
+
+#[derive(Deserialize)]
+struct CallbackQuery {
+	id: String,
+}
+
+#[get("/connect/authorize/callback")]
+async fn authorize_callback(
+	state: web::Data<State>,
+	query: web::Query<CallbackQuery>,
+) -> HttpResponse {
+	let mut inner = state.lock();
+	match inner.pending.get(&query.id) {
+		None => return HttpResponse::BadRequest().body("unknown authorize request"),
+		Some(pending) if !pending.authenticated => return redirect_to_login(&query.id),
+		Some(_) => {}
+	}
+	let Some(pending) = inner.pending.remove(&query.id) else {
+		return HttpResponse::BadRequest().body("unknown authorize request");
+	};
+
+	let code = inner.next_id("code");
+	let location = format!(
+		"{}?code={code}&scope=openid&state={}&session_state=mock",
+		pending.redirect_uri, pending.state
+	);
+	inner.codes.insert(
+		code,
+		Grant {
+			redirect_uri: pending.redirect_uri,
+			code_challenge: pending.code_challenge,
+		},
+	);
+
+	HttpResponse::Found()
+		.insert_header((header::LOCATION, location))
+		.finish()
+}
+
+#[get("/oauthredirect")]
+/// the mobile app would intercept this, we only need it to not 404
+async fn oauthredirect() -> HttpResponse {
+	HttpResponse::Ok().finish()
+}
+
+#[derive(Deserialize)]
+struct TokenForm {
+	grant_type: String,
+	client_id: String,
+
+	code: Option<String>,
+	code_verifier: Option<String>,
+	redirect_uri: Option<String>,
+
+	refresh_token: Option<String>,
+	institute_code: Option<String>,
+}
+
+#[derive(Serialize)]
+/// [TokensRaw](kreta_rs::login::TokensRaw) with public fields
+struct TokenResponse {
+	id_token: String,
+	access_token: String,
+	expires_in: i32,
+	token_type: String,
+	refresh_token: String,
+	scope: String,
+}
+
+#[post("/connect/token")]
+async fn connect_token(state: web::Data<State>, form: web::Form<TokenForm>) -> HttpResponse {
+	let mut inner = state.lock();
+	inner.token_requests += 1;
+
+	if form.client_id != CLIENT_ID {
+		return token_error("invalid_client");
+	}
+
+	let valid = match form.grant_type.as_str() {
+		"authorization_code" => {
+			let grant = form.code.as_ref().and_then(|code| inner.codes.remove(code));
+			match (grant, &form.code_verifier, &form.redirect_uri) {
+				(Some(grant), Some(verifier), Some(redirect_uri)) => {
+					grant.redirect_uri == *redirect_uri
+						&& pkce_challenge(verifier) == grant.code_challenge
+				}
+				_ => false,
+			}
+		}
+		"refresh_token" => {
+			let position = inner
+				.refresh_tokens
+				.iter()
+				.position(|token| Some(token) == form.refresh_token.as_ref());
+			match position {
+				Some(position) => {
+					inner.refresh_tokens.remove(position);
+					form.institute_code.as_ref() == Some(&state.config.inst_id)
+				}
+				None => false,
+			}
+		}
+		_ => return token_error("unsupported_grant_type"),
+	};
+	if !valid {
+		return token_error("invalid_grant");
+	}
+
+	let lifetime = state.config.access_token_lifetime;
+	let access_token = inner.next_id("access");
+	let refresh_token = inner.next_id("refresh");
+	inner
+		.access_tokens
+		.insert(access_token.clone(), Instant::now() + lifetime);
+	inner.refresh_tokens.push(refresh_token.clone());
+
+	HttpResponse::Ok().json(TokenResponse {
+		id_token: inner.next_id("id"),
+		access_token,
+		expires_in: lifetime.as_secs() as i32,
+		token_type: "Bearer".into(),
+		refresh_token,
+		scope: "openid email offline_access kreta-ellenorzo-webapi.public".into(),
+	})
+}
+
+fn token_error(error: &str) -> HttpResponse {
+	HttpResponse::BadRequest().json(serde_json::json!({ "error": error }))
+}
+
+/// base64url(sha256(verifier)), the S256 method
+fn pkce_challenge(verifier: &str) -> String {
+	let digest = sha2::Sha256::digest(verifier.as_bytes());
+	BASE64_URL_SAFE_NO_PAD.encode(digest)
+}
+
+/// the id of the pending authorize request in a ReturnUrl
+fn pending_id(return_url: &str) -> Option<&str> {
+	return_url.split("id=").nth(1)?.split('&').next()
+}
+
+fn redirect_to_login(id: &str) -> HttpResponse {
+	let return_url = format!("/connect/authorize/callback?id={id}");
+	let location = format!("/account/login?ReturnUrl={}", url_encode(&return_url));
+
+	HttpResponse::Found()
+		.insert_header((header::LOCATION, location))
+		.finish()
+}
+
+fn url_encode(s: &str) -> String {
+	s.replace('%', "%25")
+		.replace('/', "%2F")
+		.replace('?', "%3F")
+		.replace('=', "%3D")
+		.replace('&', "%26")
+}
+
+/// only the parts of the real login page that [LoginFlow](kreta_rs::login::LoginFlow) reads
+fn login_html(return_url: &str, verification_token: &str, error: bool) -> HttpResponse {
+	let error = if error {
+		r#"<div class="validation-summary-errors"><ul><li>Hibás felhasználónév vagy jelszó</li></ul></div>"#
+	} else {
+		""
+	};
+	let html = format!(
+		r#"<!DOCTYPE html>
+<html>
+<head><title>KRÉTA (mock)</title></head>
+<body>
+<form method="post" action="/account/login">
+{error}
+<input type="hidden" id="ReturnUrl" name="ReturnUrl" value="{return_url}">
+<input type="text" name="UserName">
+<input type="password" name="Password">
+<input type="text" name="InstituteCode">
+<input type="hidden" name="__RequestVerificationToken" value="{verification_token}">
+</form>
+</body>
+</html>"#
+	);
+
+	HttpResponse::Ok().content_type("text/html").body(html)
+}
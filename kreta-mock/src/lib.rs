@@ -0,0 +1,175 @@
+//! a local http server that pretends to be kreta, so the login flow and the clients can be tested without a real account \
+//! it serves the idp's login page and /connect/token, and a few ellenorzo endpoints from the json files in /kreta-mock/fixtures
+//!
+//! point kreta-rs at it with [MockServer::client_config]
+
+use std::{
+	collections::{HashMap, VecDeque},
+	net::ToSocketAddrs,
+	sync::{Mutex, MutexGuard},
+	time::{Duration, Instant},
+};
+
+use actix_web::{App, HttpServer, dev::ServerHandle, web};
+use kreta_rs::{ClientConfig, login::Credentials};
+
+mod api;
+mod idp;
+
+#[derive(Clone, Debug)]
+pub struct MockConfig {
+	pub inst_id: String,
+	pub username: String,
+	pub passwd: String,
+	/// how long the access tokens handed out by /connect/token are valid for
+	pub access_token_lifetime: Duration,
+	/// how long the ellenorzo endpoints take to answer, to see how many requests a client sends at once
+	pub response_delay: Duration,
+}
+impl Default for MockConfig {
+	fn default() -> Self {
+		Self {
+			inst_id: "mock".into(),
+			username: "mock".into(),
+			passwd: "mock".into(),
+			access_token_lifetime: Duration::from_secs(1800),
+			response_delay: Duration::ZERO,
+		}
+	}
+}
+
+pub(crate) struct State {
+	config: MockConfig,
+	inner: Mutex<Inner>,
+}
+impl State {
+	fn lock(&self) -> MutexGuard<'_, Inner> {
+		self.inner.lock().unwrap_or_else(|err| err.into_inner())
+	}
+}
+
+#[derive(Default)]
+pub(crate) struct Inner {
+	counter: u64,
+
+	/// authorize requests that haven't been turned into a code yet, by the id in the ReturnUrl
+	pending: HashMap<String, idp::Pending>,
+	/// codes that haven't been exchanged for tokens yet
+	codes: HashMap<String, idp::Grant>,
+	/// valid access tokens and when they expire
+	access_tokens: HashMap<String, Instant>,
+	/// refresh tokens that haven't been used yet, they're rotated on every refresh just like kreta's
+	refresh_tokens: Vec<String>,
+
+	token_requests: usize,
+	requests: HashMap<String, usize>,
+
+	/// status codes the next ellenorzo requests are answered with instead of the fixtures
+	failures: VecDeque<u16>,
+	in_flight: usize,
+	max_in_flight: usize,
+}
+impl Inner {
+	/// a new unique string, not random but that's not what the mock is for
+	fn next_id(&mut self, prefix: &str) -> String {
+		self.counter += 1;
+		format!("mock-{prefix}-{}", self.counter)
+	}
+}
+
+/// a running mock server, stopped when [MockServer::stop] is called
+pub struct MockServer {
+	base_url: String,
+	state: web::Data<State>,
+	handle: ServerHandle,
+}
+impl MockServer {
+	/// starts the server on a random free port on localhost
+	pub async fn start(config: MockConfig) -> anyhow::Result<Self> {
+		Self::bind(config, "127.0.0.1:0").await
+	}
+	pub async fn bind(config: MockConfig, addr: impl ToSocketAddrs) -> anyhow::Result<Self> {
+		let state = web::Data::new(State {
+			config,
+			inner: Mutex::new(Inner::default()),
+		});
+
+		let app_state = state.clone();
+		let server = HttpServer::new(move || {
+			App::new()
+				.app_data(app_state.clone())
+				.service(idp::authorize)
+				.service(idp::login_page)
+				.service(idp::login)
+				.service(idp::authorize_callback)
+				.service(idp::oauthredirect)
+				.service(idp::connect_token)
+				.service(api::ellenorzo)
+		})
+		.workers(1)
+		.bind(addr)?;
+
+		let addr = server
+			.addrs()
+			.first()
+			.copied()
+			.ok_or_else(|| anyhow::anyhow!("the mock server isn't listening on anything"))?;
+		let server = server.run();
+		let handle = server.handle();
+		tokio::spawn(server);
+
+		Ok(Self {
+			base_url: format!("http://{addr}"),
+			state,
+			handle,
+		})
+	}
+
+	pub fn base_url(&self) -> &str {
+		&self.base_url
+	}
+	/// a [ClientConfig] that sends everything to this server
+	pub fn client_config(&self) -> ClientConfig {
+		ClientConfig::local(&self.base_url)
+	}
+	/// the credentials the mock accepts
+	pub fn credentials(&self) -> Credentials {
+		let config = &self.state.config;
+		Credentials::new(
+			config.inst_id.clone(),
+			config.username.clone(),
+			config.passwd.clone(),
+		)
+	}
+
+	/// how many times /connect/token was called (logins and refreshes)
+	pub fn token_requests(&self) -> usize {
+		self.state.lock().token_requests
+	}
+	/// how many authorized requests the given endpoint (like OrarendElemek) got
+	pub fn requests(&self, endpoint: &str) -> usize {
+		self.state
+			.lock()
+			.requests
+			.get(endpoint)
+			.copied()
+			.unwrap_or_default()
+	}
+	/// makes every access token handed out so far invalid, as if they expired early
+	pub fn revoke_access_tokens(&self) {
+		self.state.lock().access_tokens.clear();
+	}
+	/// answers the next `count` authorized ellenorzo requests with `status` (like 429 or 503) instead of the fixtures
+	pub fn fail_next(&self, status: u16, count: usize) {
+		let mut inner = self.state.lock();
+		inner.failures.extend(std::iter::repeat_n(status, count));
+	}
+	/// the most ellenorzo requests that were being answered at the same time so far
+	pub fn max_in_flight(&self) -> usize {
+		self.state.lock().max_in_flight
+	}
+
+	pub async fn stop(self) {
+		self.handle.stop(false).await;
+	}
+}
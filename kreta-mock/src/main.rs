@@ -0,0 +1,22 @@
+use kreta_mock::{MockConfig, MockServer};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+	let addr = std::env::args()
+		.nth(1)
+		.unwrap_or_else(|| "127.0.0.1:8081".into());
+
+	let server = MockServer::bind(MockConfig::default(), addr).await?;
+	let credentials = server.credentials();
+	println!(
+		"kreta mock listening on {}, log in with {} / {} at {}",
+		server.base_url(),
+		credentials.username(),
+		credentials.passwd(),
+		credentials.inst_id()
+	);
+
+	tokio::signal::ctrl_c().await?;
+	server.stop().await;
+	Ok(())
+}
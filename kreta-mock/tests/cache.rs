@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use kreta_mock::{MockConfig, MockServer};
+use kreta_rs::{
+	cache::{Cache, DiskCache, MemoryCache, Ttl},
+	client::Client,
+};
+
+fn date(date: &str) -> chrono::NaiveDate {
+	chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+}
+
+#[tokio::test]
+async fn only_missing_days_are_queried() -> anyhow::Result<()> {
+	let server = MockServer::start(MockConfig::default()).await?;
+	let client = Client::full_login_with(&server.credentials(), server.client_config()).await?;
+	let cache = Cache::new(MemoryCache::default(), Ttl::default());
+	let cached = cache.with(&client, "mock");
+
+	let lessons = cached
+		.timetable_range(date("2026-02-02")..date("2026-02-16"))
+		.await?;
+	assert_eq!(lessons.len(), 30);
+	assert_eq!(server.requests("OrarendElemek"), 1);
+
+	// everything's cached already
+	let lessons = cached
+		.timetable_range(date("2026-02-09")..date("2026-02-14"))
+		.await?;
+	assert_eq!(lessons.len(), 15);
+	assert_eq!(server.requests("OrarendElemek"), 1);
+
+	// only 02-16..02-23 is missing
+	let lessons = cached
+		.timetable_range(date("2026-02-09")..date("2026-02-23"))
+		.await?;
+	assert_eq!(lessons.len(), 30);
+	assert_eq!(server.requests("OrarendElemek"), 2);
+
+	// another user doesn't see any of it
+	let other = cache.with(&client, "someone else");
+	other
+		.timetable_range(date("2026-02-09")..date("2026-02-14"))
+		.await?;
+	assert_eq!(server.requests("OrarendElemek"), 3);
+
+	server.stop().await;
+	Ok(())
+}
+
+#[tokio::test]
+async fn stale_days_are_queried_again() -> anyhow::Result<()> {
+	let server = MockServer::start(MockConfig::default()).await?;
+	let client = Client::full_login_with(&server.credentials(), server.client_config()).await?;
+	let dir = std::env::temp_dir().join(format!("kreta-cache-{}", std::process::id()));
+
+	// the fixtures are all in the past
+	let fresh = Cache::new(
+		DiskCache::new(&dir),
+		Ttl {
+			current_week: Duration::ZERO,
+			past: Duration::from_secs(60 * 60),
+		},
+	);
+	let range = date("2026-02-02")..date("2026-02-23");
+	let cached = fresh.with(&client, "mock");
+	assert_eq!(cached.absences_range(range.clone()).await?.len(), 4);
+	assert_eq!(cached.absences_range(range.clone()).await?.len(), 4);
+	assert_eq!(server.requests("Mulasztasok"), 1);
+
+	// a file per day, the temporary files they were written to are renamed over them
+	let user_dir = std::fs::read_dir(&dir)?.next().unwrap()?.path();
+	let days = std::fs::read_dir(user_dir.join("absences"))?
+		.map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+		.collect::<std::io::Result<Vec<_>>>()?;
+	assert_eq!(days.len(), 21);
+	assert!(days.iter().all(|day| day.ends_with(".json")), "{days:?}");
+
+	// same files, but nothing is fresh enough anymore
+	let stale = Cache::new(
+		DiskCache::new(&dir),
+		Ttl {
+			current_week: Duration::ZERO,
+			past: Duration::ZERO,
+		},
+	);
+	let cached = stale.with(&client, "mock");
+	assert_eq!(cached.absences_range(range).await?.len(), 4);
+	assert_eq!(server.requests("Mulasztasok"), 2);
+
+	fresh.remove_user("mock");
+	assert!(std::fs::read_dir(&dir)?.next().is_none());
+	std::fs::remove_dir_all(&dir)?;
+
+	server.stop().await;
+	Ok(())
+}
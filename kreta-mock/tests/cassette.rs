@@ -0,0 +1,76 @@
+use kreta_mock::{MockConfig, MockServer};
+use kreta_rs::{Cassette, ClientConfig, client::Client};
+
+#[tokio::test]
+async fn record_and_replay() -> anyhow::Result<()> {
+	let path = std::env::temp_dir().join(format!("kreta-cassette-{}.json", std::process::id()));
+	let config = MockConfig {
+		inst_id: "klik012345678".into(),
+		username: "70123456789".into(),
+		passwd: "hunter2-secret".into(),
+		..Default::default()
+	};
+
+	let server = MockServer::start(config).await?;
+	let recording = server
+		.client_config()
+		.with_cassette(Cassette::record(&path));
+	let client = Client::full_login_with(&server.credentials(), recording).await?;
+	let recorded = client.timetable("2026-02-02", "2026-02-09").await?;
+	let base_url = server.base_url().to_string();
+	server.stop().await;
+
+	let cassette = std::fs::read_to_string(&path)?;
+	for secret in [
+		"70123456789",
+		"hunter2-secret",
+		"klik012345678",
+		"mock-access",
+		"Minta Tanár",
+	] {
+		assert!(!cassette.contains(secret), "{secret} is in the cassette");
+	}
+
+	// nothing is listening anymore, everything has to come from the cassette
+	let replaying = ClientConfig::local(&base_url).with_cassette(Cassette::replay(&path)?);
+	let client = Client::full_login_with(&server_credentials(), replaying).await?;
+	let replayed = client.timetable("2026-02-02", "2026-02-09").await?;
+	assert_eq!(replayed.len(), recorded.len());
+	assert_eq!(replayed[0].uid, recorded[0].uid);
+
+	std::fs::remove_file(&path)?;
+	Ok(())
+}
+
+fn server_credentials() -> kreta_rs::login::Credentials {
+	kreta_rs::login::Credentials::new(
+		"klik012345678".into(),
+		"70123456789".into(),
+		"hunter2-secret".into(),
+	)
+}
+
+#[tokio::test]
+async fn profile_is_redacted() -> anyhow::Result<()> {
+	let path = std::env::temp_dir().join(format!(
+		"kreta-cassette-profile-{}.json",
+		std::process::id()
+	));
+
+	let server = MockServer::start(MockConfig::default()).await?;
+	let recording = server
+		.client_config()
+		.with_cassette(Cassette::record(&path));
+	let client = Client::full_login_with(&server.credentials(), recording).await?;
+	let profile = client.student_profile().await?;
+	server.stop().await;
+	assert_eq!(profile.name, "Minta Diák Dóra");
+
+	let cassette = std::fs::read_to_string(&path)?;
+	for secret in ["Minta Diák Dóra", "Minta Általános Iskola", "Minta Anya"] {
+		assert!(!cassette.contains(secret), "{secret} is in the cassette");
+	}
+
+	std::fs::remove_file(&path)?;
+	Ok(())
+}
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use kreta_mock::{MockConfig, MockServer};
+use kreta_rs::{Backoff, ClientConfig, KretaError, RateLimit, client::Client};
+
+async fn login(server: &MockServer, config: ClientConfig) -> anyhow::Result<Client> {
+	Client::full_login_with(&server.credentials(), config).await
+}
+
+#[tokio::test]
+async fn concurrent_requests_are_capped() -> anyhow::Result<()> {
+	let server = MockServer::start(MockConfig {
+		response_delay: Duration::from_millis(50),
+		..Default::default()
+	})
+	.await?;
+	let config = ClientConfig {
+		max_concurrent_requests: Some(2),
+		rate_limit: None,
+		..server.client_config()
+	};
+	let client = login(&server, config).await?;
+
+	let week = || client.timetable("2026-02-02", "2026-02-09");
+	let (a, b, c, d, e) = tokio::join!(week(), week(), week(), week(), week());
+	for lessons in [a, b, c, d, e] {
+		assert_eq!(lessons?.len(), 15);
+	}
+	assert_eq!(server.max_in_flight(), 2);
+
+	server.stop().await;
+	Ok(())
+}
+
+#[tokio::test]
+async fn requests_are_rate_limited() -> anyhow::Result<()> {
+	let server = MockServer::start(MockConfig::default()).await?;
+	let config = ClientConfig {
+		rate_limit: Some(RateLimit {
+			requests: 2,
+			per: Duration::from_millis(200),
+		}),
+		..server.client_config()
+	};
+	let client = login(&server, config).await?;
+
+	// 2 go through right away, the other 3 have to wait 100ms each
+	let start = Instant::now();
+	for _ in 0..5 {
+		client.exams("2026-02-02", "2026-02-09").await?;
+	}
+	assert!(start.elapsed() >= Duration::from_millis(280));
+
+	server.stop().await;
+	Ok(())
+}
+
+#[tokio::test]
+async fn rate_limited_and_failed_requests_are_retried() -> anyhow::Result<()> {
+	let server = MockServer::start(MockConfig::default()).await?;
+	let config = ClientConfig {
+		backoff: Backoff {
+			max_retries: 3,
+			base_delay: Duration::from_millis(10),
+			max_delay: Duration::from_millis(100),
+		},
+		..server.client_config()
+	};
+	let client = login(&server, config).await?;
+
+	server.fail_next(429, 1);
+	server.fail_next(502, 1);
+	let lessons = client.timetable("2026-02-02", "2026-02-09").await?;
+	assert_eq!(lessons.len(), 15);
+	assert_eq!(server.requests("OrarendElemek"), 3);
+
+	// the first try and 3 retries, then the error is returned
+	server.fail_next(500, 5);
+	let err = client.homework("2026-02-02", "2026-02-09").await.err();
+	assert!(matches!(err, Some(KretaError::Http { status: 500, .. })));
+	assert_eq!(server.requests("HaziFeladatok"), 4);
+
+	server.stop().await;
+	Ok(())
+}
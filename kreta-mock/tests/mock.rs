@@ -0,0 +1,137 @@
+use kreta_mock::{MockConfig, MockServer};
+use kreta_rs::{KretaError, client::Client, login::Credentials};
+
+#[tokio::test]
+async fn login_and_refresh() -> anyhow::Result<()> {
+	let server = MockServer::start(MockConfig::default()).await?;
+	let client = Client::full_login_with(&server.credentials(), server.client_config()).await?;
+
+	let lessons = client.timetable("2026-02-02", "2026-02-09").await?;
+	assert_eq!(lessons.len(), 15);
+
+	client.refresh().await?;
+	assert_eq!(server.token_requests(), 2);
+
+	// the old access token is gone, the client should refresh and retry on its own
+	server.revoke_access_tokens();
+	let homework = client.homework("2026-02-02", "2026-02-06").await?;
+	assert!(!homework.is_empty());
+	assert_eq!(server.token_requests(), 3);
+
+	let wrong = Credentials::new("mock".into(), "mock".into(), "wrong".into());
+	let err = Client::full_login_with(&wrong, server.client_config())
+		.await
+		.err()
+		.expect("logged in with a wrong password");
+	assert!(matches!(
+		KretaError::find(&err),
+		Some(KretaError::InvalidCredentials)
+	));
+
+	server.stop().await;
+	Ok(())
+}
+
+#[tokio::test]
+async fn range_boundaries() -> anyhow::Result<()> {
+	let server = MockServer::start(MockConfig::default()).await?;
+	let client = Client::full_login_with(&server.credentials(), server.client_config()).await?;
+
+	// datumIg is exclusive for the timetable, friday isn't included
+	let lessons = client.timetable("2026-02-02", "2026-02-06").await?;
+	assert_eq!(lessons.len(), 12);
+	// but inclusive for everything else
+	let absences = client.absences("2026-02-03", "2026-02-03").await?;
+	assert_eq!(absences.len(), 1);
+
+	let too_long = client.homework("2026-02-02", "2026-03-02").await;
+	assert!(matches!(
+		too_long,
+		Err(KretaError::Http { status: 400, .. })
+	));
+
+	server.stop().await;
+	Ok(())
+}
+
+#[tokio::test]
+async fn ranges_have_no_gaps_or_duplicates() -> anyhow::Result<()> {
+	let server = MockServer::start(MockConfig::default()).await?;
+	let client = Client::full_login_with(&server.credentials(), server.client_config()).await?;
+
+	// the fixtures cover 2026-02-02 to 2026-03-13, longer than a single request can be
+	let date = |date: &str| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d");
+	let range = date("2026-02-02")?..date("2026-03-14")?;
+
+	assert_eq!(client.timetable_range(range.clone()).await?.len(), 90);
+	assert_eq!(client.homework_range(range.clone()).await?.len(), 15);
+	assert_eq!(client.exams_range(range.clone()).await?.len(), 6);
+	assert_eq!(client.absences_range(range).await?.len(), 8);
+
+	// half-open: the first day of the next chunk isn't part of this one
+	let lessons = client
+		.timetable_range(date("2026-02-02")?..date("2026-02-06")?)
+		.await?;
+	assert_eq!(lessons.len(), 12);
+	let absences = client
+		.absences_range(date("2026-02-03")?..date("2026-02-04")?)
+		.await?;
+	assert_eq!(absences.len(), 1);
+
+	server.stop().await;
+	Ok(())
+}
+
+#[tokio::test]
+async fn fixtures_convert_to_model() -> anyhow::Result<()> {
+	use kreta_rs::model::{
+		Absence, AbsenceKind, Exam, ExamMethod, ExcuseStatus, Homework, Lesson, LessonStatus,
+		Presence,
+	};
+
+	let server = MockServer::start(MockConfig::default()).await?;
+	let client = Client::full_login_with(&server.credentials(), server.client_config()).await?;
+
+	let date = |date: &str| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d");
+	let range = date("2026-02-02")?..date("2026-03-14")?;
+
+	let lessons = client.timetable_range(range.clone()).await?;
+	let lessons = lessons
+		.into_iter()
+		.map(Lesson::try_from)
+		.collect::<anyhow::Result<Vec<_>>>()?;
+	for lesson in &lessons {
+		// kreta sends midnight in budapest as 23:00 the day before in utc
+		assert_eq!(lesson.date.date_naive(), lesson.start.date_naive());
+		assert_eq!(lesson.status, LessonStatus::Logged);
+		assert_eq!(lesson.presence, Some(Presence::Present));
+	}
+
+	let homework = client.homework_range(range.clone()).await?;
+	for homework in homework {
+		let homework = Homework::try_from(homework)?;
+		assert!(homework.date_assigned < homework.date_deadline);
+	}
+
+	let exams = client.exams_range(range.clone()).await?;
+	for exam in exams {
+		let exam = Exam::try_from(exam)?;
+		assert_eq!(exam.method, ExamMethod::Quiz);
+	}
+
+	let absences = client.absences_range(range).await?;
+	for absence in absences {
+		let absence = Absence::try_from(absence)?;
+		match absence.kind {
+			AbsenceKind::Absence => assert_eq!(absence.excuse_status, ExcuseStatus::Excused),
+			AbsenceKind::Late => {
+				assert_eq!(absence.excuse_status, ExcuseStatus::ToBeExcused);
+				assert!(absence.late_by_minutes.is_some());
+			}
+			AbsenceKind::Other(other) => panic!("unexpected absence type {other}"),
+		}
+	}
+
+	server.stop().await;
+	Ok(())
+}
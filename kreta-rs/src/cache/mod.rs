@@ -0,0 +1,346 @@
+//! a cache in front of [Client] for the timetable, homework, exams and absences \
+//! everything is stored per day, so asking for a range that partly overlaps what's already cached
+//! only queries kreta for the days that are missing or stale. days in the current week (or later) go stale
+//! sooner than past ones, see [Ttl]
+//!
+//! ```ignore
+//! let cache = Cache::new(MemoryCache::default(), Ttl::default());
+//! let lessons = cache.with(&client, username).timetable_range(range).await?;
+//! ```
+
+use std::{
+	future::Future,
+	ops::Range,
+	sync::Arc,
+	time::{Duration, SystemTime},
+};
+
+use anyhow::Context;
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+	client::{
+		Client, absences::AbsenceRaw, exam::ExamRaw, homework::HomeworkRaw, timetable::LessonRaw,
+	},
+	model::TIMEZONE,
+};
+
+mod store;
+pub use store::{DiskCache, MemoryCache};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Kind {
+	Timetable,
+	Homework,
+	Exams,
+	Absences,
+}
+impl Kind {
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Timetable => "timetable",
+			Self::Homework => "homework",
+			Self::Exams => "exams",
+			Self::Absences => "absences",
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+	/// whose data this is, anything that tells the users apart (like their username)
+	pub user: String,
+	pub kind: Kind,
+	pub day: NaiveDate,
+}
+
+#[derive(Clone, Debug)]
+/// everything kreta returned for a single day, as json
+pub struct CachedDay {
+	pub fetched_at: SystemTime,
+	pub items: Vec<serde_json::Value>,
+}
+
+/// where [Cache] keeps its days. errors are only printed by [Cache], a broken cache shouldn't fail requests
+pub trait CacheStore: Send + Sync {
+	fn get(&self, key: &CacheKey) -> anyhow::Result<Option<CachedDay>>;
+	fn put(&self, key: &CacheKey, day: CachedDay) -> anyhow::Result<()>;
+	/// forgets everything stored for `user`
+	fn remove_user(&self, user: &str) -> anyhow::Result<()>;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// how long a cached day is used before it's queried again
+pub struct Ttl {
+	/// days from the monday of the current week onwards, lessons get cancelled and homework gets added here
+	pub current_week: Duration,
+	/// days before the current week. absences still get excused, so this shouldn't be forever either
+	pub past: Duration,
+}
+impl Default for Ttl {
+	fn default() -> Self {
+		Self {
+			current_week: Duration::from_secs(60 * 15),
+			past: Duration::from_secs(60 * 60 * 24),
+		}
+	}
+}
+impl Ttl {
+	/// the ttl of `day`, if today is `today`
+	pub fn of(&self, day: NaiveDate, today: NaiveDate) -> Duration {
+		let monday = today - chrono::Duration::days(today.weekday().num_days_from_monday() as _);
+		if day >= monday {
+			self.current_week
+		} else {
+			self.past
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct Cache {
+	store: Arc<dyn CacheStore>,
+	ttl: Ttl,
+}
+impl Cache {
+	pub fn new(store: impl CacheStore + 'static, ttl: Ttl) -> Self {
+		Self {
+			store: Arc::new(store),
+			ttl,
+		}
+	}
+
+	/// `client` with this cache in front of it. `user` is what the cached data is stored under,
+	/// it has to be different for every user sharing this cache
+	pub fn with<'a>(&'a self, client: &'a Client, user: &'a str) -> CachedClient<'a> {
+		CachedClient {
+			cache: self,
+			client,
+			user,
+		}
+	}
+
+	/// forgets everything cached for `user`, like when they log out
+	pub fn remove_user(&self, user: &str) {
+		if let Err(err) = self.store.remove_user(user) {
+			eprintln!("failed to remove the cache of {user}: {err:?}");
+		}
+	}
+}
+
+/// the *_range functions of [Client], but only the missing and stale days are queried
+pub struct CachedClient<'a> {
+	cache: &'a Cache,
+	client: &'a Client,
+	user: &'a str,
+}
+impl CachedClient<'_> {
+	pub fn client(&self) -> &Client {
+		self.client
+	}
+
+	/// [Client::timetable_range]
+	pub async fn timetable_range(&self, range: Range<NaiveDate>) -> anyhow::Result<Vec<LessonRaw>> {
+		self.range(range, |range| self.client.timetable_range(range))
+			.await
+	}
+	/// [Client::homework_range]
+	pub async fn homework_range(
+		&self,
+		range: Range<NaiveDate>,
+	) -> anyhow::Result<Vec<HomeworkRaw>> {
+		self.range(range, |range| self.client.homework_range(range))
+			.await
+	}
+	/// [Client::exams_range]
+	pub async fn exams_range(&self, range: Range<NaiveDate>) -> anyhow::Result<Vec<ExamRaw>> {
+		self.range(range, |range| self.client.exams_range(range))
+			.await
+	}
+	/// [Client::absences_range]
+	pub async fn absences_range(&self, range: Range<NaiveDate>) -> anyhow::Result<Vec<AbsenceRaw>> {
+		self.range(range, |range| self.client.absences_range(range))
+			.await
+	}
+
+	async fn range<T, F, Fut>(&self, range: Range<NaiveDate>, fetch: F) -> anyhow::Result<Vec<T>>
+	where
+		T: Cacheable,
+		F: Fn(Range<NaiveDate>) -> Fut,
+		Fut: Future<Output = anyhow::Result<Vec<T>>>,
+	{
+		let now = SystemTime::now();
+		let today = Utc::now().with_timezone(&TIMEZONE).date_naive();
+
+		let mut buf = Vec::new();
+		let mut stale = Vec::new();
+		for day in range.start.iter_days().take_while(|day| *day < range.end) {
+			let cached = self.get(&self.key(T::KIND, day));
+			let fresh = cached.as_ref().is_some_and(|cached| {
+				let age = now.duration_since(cached.fetched_at).unwrap_or_default();
+				age < self.cache.ttl.of(day, today)
+			});
+
+			let items = match cached {
+				Some(cached) if fresh => cached
+					.items
+					.into_iter()
+					.map(serde_json::from_value)
+					.collect::<Result<Vec<T>, _>>(),
+				_ => {
+					stale.push(day);
+					continue;
+				}
+			};
+			match items {
+				Ok(items) => buf.extend(items),
+				// probably saved before the raw structs changed, just query it again
+				Err(err) => {
+					eprintln!(
+						"failed to deserialize cached {} of {day}: {err}",
+						T::KIND.name()
+					);
+					stale.push(day);
+				}
+			}
+		}
+
+		for stale in contiguous(&stale) {
+			let fetched = fetch(stale.clone())
+				.await
+				.with_context(|| format!("while querying {} in {stale:?}", T::KIND.name()))?;
+			self.store(T::KIND, stale, &fetched, now);
+			buf.extend(fetched);
+		}
+		crate::client::range::dedup_by_uid(&mut buf, T::uid);
+
+		Ok(buf)
+	}
+
+	/// stores what kreta returned for `range` day by day, days without anything included \
+	/// if anything can't be sorted into a day nothing is stored, the request still gets what kreta returned
+	fn store<T: Cacheable>(
+		&self,
+		kind: Kind,
+		range: Range<NaiveDate>,
+		fetched: &[T],
+		fetched_at: SystemTime,
+	) {
+		let days = match by_day(range.clone(), fetched) {
+			Ok(days) => days,
+			Err(err) => {
+				eprintln!("not caching {} in {range:?}: {err:?}", kind.name());
+				return;
+			}
+		};
+
+		for (day, items) in days {
+			let key = self.key(kind, day);
+			if let Err(err) = self.cache.store.put(&key, CachedDay { fetched_at, items }) {
+				eprintln!("failed to cache {} of {day}: {err:?}", kind.name());
+			}
+		}
+	}
+
+	fn get(&self, key: &CacheKey) -> Option<CachedDay> {
+		match self.cache.store.get(key) {
+			Ok(cached) => cached,
+			Err(err) => {
+				eprintln!(
+					"failed to read cached {} of {}: {err:?}",
+					key.kind.name(),
+					key.day
+				);
+				None
+			}
+		}
+	}
+	fn key(&self, kind: Kind, day: NaiveDate) -> CacheKey {
+		CacheKey {
+			user: self.user.into(),
+			kind,
+			day,
+		}
+	}
+}
+
+/// every day of `range` with the json of the items on it, days without anything included
+fn by_day<T: Cacheable>(
+	range: Range<NaiveDate>,
+	items: &[T],
+) -> anyhow::Result<Vec<(NaiveDate, Vec<serde_json::Value>)>> {
+	let mut days = range
+		.start
+		.iter_days()
+		.take_while(|day| *day < range.end)
+		.map(|day| (day, Vec::new()))
+		.collect::<Vec<_>>();
+
+	for item in items {
+		let day = item
+			.day()
+			.with_context(|| format!("{} {} has no valid date", T::KIND.name(), item.uid()))?;
+		// kreta returns things outside the range sometimes, those are left to their own query
+		if let Some((_, items)) = days.iter_mut().find(|(d, _)| *d == day) {
+			items.push(serde_json::to_value(item)?);
+		}
+	}
+	Ok(days)
+}
+
+/// sorted days into as few half-open ranges as possible
+fn contiguous(days: &[NaiveDate]) -> Vec<Range<NaiveDate>> {
+	let mut ranges: Vec<Range<NaiveDate>> = Vec::new();
+	for day in days {
+		match ranges.last_mut() {
+			Some(last) if last.end == *day => last.end = *day + chrono::Duration::days(1),
+			_ => ranges.push(*day..*day + chrono::Duration::days(1)),
+		}
+	}
+	ranges
+}
+
+/// the raw structs the cache can store
+trait Cacheable: Serialize + DeserializeOwned {
+	const KIND: Kind;
+	fn uid(&self) -> &str;
+	/// the day kreta filters this by
+	fn day(&self) -> anyhow::Result<NaiveDate>;
+}
+impl Cacheable for LessonRaw {
+	const KIND: Kind = Kind::Timetable;
+	fn uid(&self) -> &str {
+		&self.uid
+	}
+	fn day(&self) -> anyhow::Result<NaiveDate> {
+		Ok(crate::model::parse_time("lesson date", &self.date)?.date_naive())
+	}
+}
+impl Cacheable for HomeworkRaw {
+	const KIND: Kind = Kind::Homework;
+	fn uid(&self) -> &str {
+		&self.uid
+	}
+	fn day(&self) -> anyhow::Result<NaiveDate> {
+		Ok(crate::model::parse_time("homework deadline", &self.date_deadline)?.date_naive())
+	}
+}
+impl Cacheable for ExamRaw {
+	const KIND: Kind = Kind::Exams;
+	fn uid(&self) -> &str {
+		&self.uid
+	}
+	fn day(&self) -> anyhow::Result<NaiveDate> {
+		Ok(crate::model::parse_time("exam date", &self.date)?.date_naive())
+	}
+}
+impl Cacheable for AbsenceRaw {
+	const KIND: Kind = Kind::Absences;
+	fn uid(&self) -> &str {
+		&self.uid
+	}
+	fn day(&self) -> anyhow::Result<NaiveDate> {
+		Ok(crate::model::parse_time("absence date", &self.date)?.date_naive())
+	}
+}
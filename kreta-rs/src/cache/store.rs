@@ -0,0 +1,145 @@
+use std::{
+	collections::HashMap,
+	io::{ErrorKind, Write},
+	path::{Path, PathBuf},
+	sync::{
+		Mutex,
+		atomic::{AtomicU64, Ordering},
+	},
+	time::{Duration, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use super::{CacheKey, CacheStore, CachedDay};
+
+#[derive(Default)]
+/// forgets everything on restart
+pub struct MemoryCache {
+	map: Mutex<HashMap<CacheKey, CachedDay>>,
+}
+impl CacheStore for MemoryCache {
+	fn get(&self, key: &CacheKey) -> anyhow::Result<Option<CachedDay>> {
+		let map = self.map.lock().unwrap_or_else(|err| err.into_inner());
+		Ok(map.get(key).cloned())
+	}
+	fn put(&self, key: &CacheKey, day: CachedDay) -> anyhow::Result<()> {
+		let mut map = self.map.lock().unwrap_or_else(|err| err.into_inner());
+		map.insert(key.clone(), day);
+		Ok(())
+	}
+	fn remove_user(&self, user: &str) -> anyhow::Result<()> {
+		let mut map = self.map.lock().unwrap_or_else(|err| err.into_inner());
+		map.retain(|key, _| key.user != user);
+		Ok(())
+	}
+}
+
+/// a json file per user, kind and day: `{dir}/{sha256 of the user}/{kind}/{yyyy-mm-dd}.json` \
+/// the files aren't encrypted, keep `dir` somewhere only you can read
+pub struct DiskCache {
+	dir: PathBuf,
+}
+impl DiskCache {
+	pub fn new(dir: impl Into<PathBuf>) -> Self {
+		Self { dir: dir.into() }
+	}
+
+	/// usernames can contain anything, and they don't need to be readable from the directory listing either
+	fn user_dir(&self, user: &str) -> PathBuf {
+		use sha2::Digest;
+
+		let digest = sha2::Sha256::digest(user.as_bytes());
+		let hex = digest
+			.iter()
+			.map(|byte| format!("{byte:02x}"))
+			.collect::<String>();
+		self.dir.join(hex)
+	}
+	fn path(&self, key: &CacheKey) -> PathBuf {
+		self.user_dir(&key.user)
+			.join(key.kind.name())
+			.join(format!("{}.json", key.day.format("%Y-%m-%d")))
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+/// a [CachedDay] as it's saved to disk
+struct SavedDay {
+	/// seconds since the unix epoch
+	fetched_at: u64,
+	items: Vec<serde_json::Value>,
+}
+
+impl CacheStore for DiskCache {
+	fn get(&self, key: &CacheKey) -> anyhow::Result<Option<CachedDay>> {
+		let path = self.path(key);
+		let file = match std::fs::read_to_string(&path) {
+			Ok(file) => file,
+			Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+			Err(err) => Err(err).with_context(|| format!("while reading {}", path.display()))?,
+		};
+		let saved: SavedDay = serde_json::from_str(&file)
+			.with_context(|| format!("while deserializing {}", path.display()))?;
+
+		Ok(Some(CachedDay {
+			fetched_at: UNIX_EPOCH + Duration::from_secs(saved.fetched_at),
+			items: saved.items,
+		}))
+	}
+	fn put(&self, key: &CacheKey, day: CachedDay) -> anyhow::Result<()> {
+		let path = self.path(key);
+		if let Some(parent) = path.parent() {
+			std::fs::create_dir_all(parent)
+				.with_context(|| format!("while creating {}", parent.display()))?;
+		}
+
+		let saved = SavedDay {
+			fetched_at: day
+				.fetched_at
+				.duration_since(UNIX_EPOCH)
+				.unwrap_or_default()
+				.as_secs(),
+			items: day.items,
+		};
+		let json = serde_json::to_string(&saved)?;
+		write_atomic(&path, &json)
+	}
+	fn remove_user(&self, user: &str) -> anyhow::Result<()> {
+		let dir = self.user_dir(user);
+		match std::fs::remove_dir_all(&dir) {
+			Err(err) if err.kind() != ErrorKind::NotFound => {
+				Err(err).with_context(|| format!("while removing {}", dir.display()))
+			}
+			_ => Ok(()),
+		}
+	}
+}
+
+/// writes `contents` to a temporary file next to `path` and renames it over `path`,
+/// so a crash halfway through never leaves a truncated day behind that would be read back as fresh \
+/// the temporary file is unique, two requests can cache the same day at the same time
+fn write_atomic(path: &Path, contents: &str) -> anyhow::Result<()> {
+	static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+	let mut tmp = path.as_os_str().to_owned();
+	tmp.push(format!(
+		".{}-{}.tmp",
+		std::process::id(),
+		COUNTER.fetch_add(1, Ordering::Relaxed)
+	));
+	let tmp = PathBuf::from(tmp);
+
+	let mut file =
+		std::fs::File::create(&tmp).with_context(|| format!("while creating {}", tmp.display()))?;
+	let written = file
+		.write_all(contents.as_bytes())
+		.and_then(|_| file.sync_all())
+		.and_then(|_| std::fs::rename(&tmp, path));
+	if let Err(err) = written {
+		let _ = std::fs::remove_file(&tmp);
+		return Err(err).with_context(|| format!("while writing {}", path.display()));
+	}
+	Ok(())
+}
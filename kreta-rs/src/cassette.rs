@@ -0,0 +1,506 @@
+//! records every request kreta-rs sends and the response it got into a json file (a cassette),
+//! and serves them back later without touching the network, so bugs can be reproduced and
+//! regression tests written from real traffic without anyone's credentials
+//!
+//! everything is redacted before it's written to disk:
+//! - tokens, codes, passwords and the like, by the name of the query parameter, form field or json key they're in
+//! - personal data (names of students, parents and teachers, addresses, emails, etc), by json key
+//!   (and by endpoint for keys that are only personal in one of them, like the student's `Nev` in TanuloAdatlap)
+//! - every value redacted above is also scrubbed from everywhere else it shows up,
+//!   like the institute code in the api urls or the verification token in the login page \
+//!   anything else can be added by hand with [Cassette::redact]
+//!
+//! replaying matches requests by method, path and (redacted) query, in the order they were recorded \
+//! usage: `ClientConfig::default().with_cassette(Cassette::record("kreta.json"))`
+
+use std::{
+	path::{Path, PathBuf},
+	sync::Mutex,
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use reqwest::{ResponseBuilderExt, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::error::KretaError;
+
+const REDACTED: &str = "REDACTED";
+
+/// query parameters and form fields that are secrets no matter what
+const SECRET_FIELDS: &[&str] = &[
+	"code",
+	"state",
+	"nonce",
+	"session_state",
+	"code_challenge",
+	"code_verifier",
+	"access_token",
+	"refresh_token",
+	"id_token",
+	"institute_code",
+	"UserName",
+	"Password",
+	"InstituteCode",
+	"__RequestVerificationToken",
+];
+
+/// json keys whose values (and everything inside them) are redacted in responses
+const PERSONAL_KEYS: &[&str] = &[
+	"access_token",
+	"refresh_token",
+	"id_token",
+	// TanuloAdatlap
+	"SzuletesiNev",
+	"AnyjaNeve",
+	"SzuletesiDatum",
+	"SzuletesiHely",
+	"EmailCim",
+	"Telefonszam",
+	"Cimek",
+	"Gondviselok",
+	"Bankszamla",
+	// teachers
+	"Tanar",
+	"TanarNeve",
+	"HelyettesTanarNeve",
+	"RogzitoTanarNeve",
+	"ErtekeloTanarNeve",
+	"KeszitoTanarNeve",
+	"RogzitoNeve",
+	// messages
+	"feladoNev",
+	"uzenetFeladoNev",
+	"cimzettLista",
+];
+
+/// json keys that are only personal in the response of one endpoint, by the end of its path \
+/// `Nev` is the student's name in TanuloAdatlap but the name of a subject or a category everywhere else
+const PERSONAL_KEYS_BY_PATH: &[(&str, &[&str])] = &[(
+	"/ellenorzo/v3/sajat/TanuloAdatlap",
+	&["Nev", "IntezmenyNev"],
+)];
+
+/// values shorter than this aren't scrubbed from everywhere, a 2 letter password would wreck the whole cassette
+const MIN_LITERAL_LEN: usize = 4;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+	/// sends requests like normal and saves them
+	Record,
+	/// never sends anything, answers from the cassette
+	Replay,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Interaction {
+	pub request: RecordedRequest,
+	pub response: RecordedResponse,
+}
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedRequest {
+	pub method: String,
+	pub url: String,
+	pub body: Option<String>,
+}
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedResponse {
+	pub status: u16,
+	/// where the request ended up after following redirects
+	pub url: String,
+	/// only content-type and retry-after, cookies and such are left out
+	pub headers: Vec<(String, String)>,
+	pub body: String,
+	#[serde(default)]
+	/// if true, body is base64 (attachments)
+	pub binary: bool,
+}
+
+#[derive(Debug, Default)]
+struct State {
+	/// unredacted while recording, straight from the file while replaying
+	interactions: Vec<Interaction>,
+	/// while replaying, which interactions have been served already
+	used: Vec<bool>,
+	/// every secret seen so far, scrubbed from everything that's saved
+	literals: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Cassette {
+	path: PathBuf,
+	mode: Mode,
+	state: Mutex<State>,
+}
+
+impl Cassette {
+	/// starts recording into `path`, overwriting it. the file is saved after every request
+	pub fn record(path: impl Into<PathBuf>) -> Self {
+		Self {
+			path: path.into(),
+			mode: Mode::Record,
+			state: Default::default(),
+		}
+	}
+	/// loads a cassette saved with [Cassette::record]
+	pub fn replay(path: impl Into<PathBuf>) -> Result<Self, KretaError> {
+		let path = path.into();
+		let file = std::fs::read_to_string(&path)
+			.map_err(|err| cassette_error(&path, &err.to_string()))?;
+		let interactions: Vec<Interaction> =
+			serde_json::from_str(&file).map_err(|err| cassette_error(&path, &err.to_string()))?;
+
+		let state = State {
+			used: vec![false; interactions.len()],
+			interactions,
+			literals: Vec::new(),
+		};
+		Ok(Self {
+			path,
+			mode: Mode::Replay,
+			state: Mutex::new(state),
+		})
+	}
+
+	pub fn mode(&self) -> Mode {
+		self.mode
+	}
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// scrubs `literal` from everything that's saved from now on (and everything saved before, on the next save) \
+	/// use it for things that can't be recognized automatically, like your own name
+	pub fn redact(&self, literal: &str) {
+		self.lock().learn(literal);
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+		self.state.lock().unwrap_or_else(|err| err.into_inner())
+	}
+
+	pub(crate) async fn execute(
+		&self,
+		client: &reqwest::Client,
+		req: reqwest::Request,
+	) -> Result<reqwest::Response, KretaError> {
+		let request = RecordedRequest {
+			method: req.method().to_string(),
+			url: req.url().to_string(),
+			body: req
+				.body()
+				.and_then(|body| body.as_bytes())
+				.map(|body| String::from_utf8_lossy(body).into_owned()),
+		};
+		self.lock().learn_request(&request);
+
+		match self.mode {
+			Mode::Record => self.record_send(client, req, request).await,
+			Mode::Replay => self.replay_find(&request),
+		}
+	}
+
+	async fn record_send(
+		&self,
+		client: &reqwest::Client,
+		req: reqwest::Request,
+		request: RecordedRequest,
+	) -> Result<reqwest::Response, KretaError> {
+		let resp = client.execute(req).await?;
+
+		let status = resp.status();
+		let url = resp.url().clone();
+		let headers = resp.headers().clone();
+		let bytes = resp.bytes().await?;
+
+		let (body, binary) = match std::str::from_utf8(&bytes) {
+			Ok(body) => (body.to_string(), false),
+			Err(_) => (BASE64_STANDARD.encode(&bytes), true),
+		};
+		let kept_headers = headers
+			.iter()
+			.filter(|(name, _)| {
+				*name == reqwest::header::CONTENT_TYPE || *name == reqwest::header::RETRY_AFTER
+			})
+			.filter_map(|(name, val)| Some((name.to_string(), val.to_str().ok()?.to_string())))
+			.collect();
+		let response = RecordedResponse {
+			status: status.as_u16(),
+			url: url.to_string(),
+			headers: kept_headers,
+			body,
+			binary,
+		};
+
+		{
+			let mut state = self.lock();
+			state.learn_response(&response);
+			state.interactions.push(Interaction { request, response });
+			state.save(&self.path)?;
+		}
+
+		let mut builder = http::Response::builder().status(status).url(url);
+		for (name, val) in headers.iter() {
+			builder = builder.header(name, val);
+		}
+		let resp = builder
+			.body(bytes)
+			.map_err(|err| cassette_error(&self.path, &err.to_string()))?;
+		Ok(resp.into())
+	}
+
+	fn replay_find(&self, request: &RecordedRequest) -> Result<reqwest::Response, KretaError> {
+		let mut state = self.lock();
+		let wanted = match_key(&state.redact_url(&request.url));
+
+		let index = state
+			.interactions
+			.iter()
+			.zip(&state.used)
+			.position(|(interaction, used)| {
+				!used
+					&& interaction.request.method == request.method
+					&& match_key(&interaction.request.url) == wanted
+			});
+		let Some(index) = index else {
+			return Err(cassette_error(
+				&self.path,
+				&format!("no recorded response for {} {wanted}", request.method),
+			));
+		};
+		state.used[index] = true;
+		let recorded = &state.interactions[index].response;
+
+		let url = Url::parse(&recorded.url)
+			.map_err(|err| cassette_error(&self.path, &err.to_string()))?;
+		let body = if recorded.binary {
+			BASE64_STANDARD
+				.decode(&recorded.body)
+				.map_err(|err| cassette_error(&self.path, &err.to_string()))?
+		} else {
+			recorded.body.clone().into_bytes()
+		};
+
+		let mut builder = http::Response::builder().status(recorded.status).url(url);
+		for (name, val) in &recorded.headers {
+			builder = builder.header(name, val);
+		}
+		let resp = builder
+			.body(body)
+			.map_err(|err| cassette_error(&self.path, &err.to_string()))?;
+		Ok(resp.into())
+	}
+}
+
+impl State {
+	fn learn(&mut self, literal: &str) {
+		if literal.len() < MIN_LITERAL_LEN || literal == REDACTED {
+			return;
+		}
+		if !self.literals.iter().any(|known| known == literal) {
+			self.literals.push(literal.into());
+			// longest first, so a literal containing another one is scrubbed whole
+			self.literals
+				.sort_by_key(|literal| std::cmp::Reverse(literal.len()));
+		}
+	}
+
+	fn learn_request(&mut self, request: &RecordedRequest) {
+		let mut secrets = Vec::new();
+		if let Ok(url) = Url::parse(&request.url) {
+			secrets.extend(secret_pairs(url.query_pairs()));
+		}
+		if let Some(body) = &request.body {
+			secrets.extend(secret_pairs(form_pairs(body)));
+		}
+		for secret in secrets {
+			self.learn(&secret);
+		}
+	}
+
+	fn learn_response(&mut self, response: &RecordedResponse) {
+		if response.binary {
+			return;
+		}
+		let Ok(json) = serde_json::from_str::<serde_json::Value>(&response.body) else {
+			return;
+		};
+		let mut personal = Vec::new();
+		collect_personal(&json, &personal_keys(&response.url), false, &mut personal);
+		for value in personal {
+			self.learn(&value);
+		}
+	}
+
+	fn scrub(&self, text: &str) -> String {
+		let mut text = text.to_string();
+		for literal in &self.literals {
+			text = text.replace(literal.as_str(), REDACTED);
+		}
+		text
+	}
+
+	fn redact_url(&self, url: &str) -> String {
+		let Ok(mut url) = Url::parse(url) else {
+			return self.scrub(url);
+		};
+		if url.query().is_some() {
+			let pairs = redact_pairs(url.query_pairs());
+			url.query_pairs_mut().clear().extend_pairs(pairs);
+		}
+		self.scrub(url.as_str())
+	}
+
+	/// `url` decides which json keys are personal, see [personal_keys]
+	fn redact_body(&self, url: &str, body: &str) -> String {
+		if let Ok(mut json) = serde_json::from_str::<serde_json::Value>(body) {
+			redact_json(&mut json, &personal_keys(url), false);
+			let json = serde_json::to_string(&json).unwrap_or_default();
+			return self.scrub(&json);
+		}
+		// form bodies
+		if !body.contains(char::is_whitespace) && body.contains('=') {
+			let mut url = Url::parse("http://localhost/").expect("valid url");
+			url.query_pairs_mut()
+				.extend_pairs(redact_pairs(form_pairs(body)));
+			return self.scrub(url.query().unwrap_or_default());
+		}
+		self.scrub(body)
+	}
+
+	fn redacted(&self) -> Vec<Interaction> {
+		self.interactions
+			.iter()
+			.map(|Interaction { request, response }| Interaction {
+				request: RecordedRequest {
+					method: request.method.clone(),
+					url: self.redact_url(&request.url),
+					body: request
+						.body
+						.as_deref()
+						.map(|body| self.redact_body(&request.url, body)),
+				},
+				response: RecordedResponse {
+					status: response.status,
+					url: self.redact_url(&response.url),
+					headers: response.headers.clone(),
+					body: match response.binary {
+						true => response.body.clone(),
+						false => self.redact_body(&response.url, &response.body),
+					},
+					binary: response.binary,
+				},
+			})
+			.collect()
+	}
+
+	/// writes every interaction so far, so the earlier ones get scrubbed of anything learned since
+	fn save(&self, path: &Path) -> Result<(), KretaError> {
+		let json = serde_json::to_string_pretty(&self.redacted())
+			.map_err(|err| cassette_error(path, &err.to_string()))?;
+		std::fs::write(path, json).map_err(|err| cassette_error(path, &err.to_string()))
+	}
+}
+
+/// what a request is matched by when replaying: path and query, the host is ignored so a mock on a random port still matches
+fn match_key(url: &str) -> String {
+	match Url::parse(url) {
+		Ok(url) => match url.query() {
+			Some(query) => format!("{}?{query}", url.path()),
+			None => url.path().into(),
+		},
+		Err(_) => url.into(),
+	}
+}
+
+fn form_pairs(body: &str) -> Vec<(String, String)> {
+	let Ok(url) = Url::parse(&format!("http://localhost/?{body}")) else {
+		return Vec::new();
+	};
+	url.query_pairs()
+		.map(|(key, val)| (key.into_owned(), val.into_owned()))
+		.collect()
+}
+
+fn secret_pairs<K: AsRef<str>, V: AsRef<str>>(
+	pairs: impl IntoIterator<Item = (K, V)>,
+) -> Vec<String> {
+	pairs
+		.into_iter()
+		.filter(|(key, _)| SECRET_FIELDS.contains(&key.as_ref()))
+		.map(|(_, val)| val.as_ref().to_string())
+		.collect()
+}
+
+fn redact_pairs<K: AsRef<str>, V: AsRef<str>>(
+	pairs: impl IntoIterator<Item = (K, V)>,
+) -> Vec<(String, String)> {
+	pairs
+		.into_iter()
+		.map(|(key, val)| {
+			let key = key.as_ref().to_string();
+			let val = match SECRET_FIELDS.contains(&key.as_str()) {
+				true => REDACTED.to_string(),
+				false => val.as_ref().to_string(),
+			};
+			(key, val)
+		})
+		.collect()
+}
+
+/// [PERSONAL_KEYS] and the ones in [PERSONAL_KEYS_BY_PATH] that apply to `url`
+fn personal_keys(url: &str) -> Vec<&'static str> {
+	let path = match Url::parse(url) {
+		Ok(url) => url.path().to_string(),
+		Err(_) => url.to_string(),
+	};
+	let mut keys = PERSONAL_KEYS.to_vec();
+	for (suffix, extra) in PERSONAL_KEYS_BY_PATH {
+		if path.ends_with(suffix) {
+			keys.extend_from_slice(extra);
+		}
+	}
+	keys
+}
+
+fn collect_personal(
+	json: &serde_json::Value,
+	keys: &[&str],
+	personal: bool,
+	buf: &mut Vec<String>,
+) {
+	match json {
+		serde_json::Value::String(val) if personal => buf.push(val.clone()),
+		serde_json::Value::Array(vals) => {
+			for val in vals {
+				collect_personal(val, keys, personal, buf);
+			}
+		}
+		serde_json::Value::Object(map) => {
+			for (key, val) in map {
+				collect_personal(val, keys, personal || keys.contains(&key.as_str()), buf);
+			}
+		}
+		_ => {}
+	}
+}
+
+fn redact_json(json: &mut serde_json::Value, keys: &[&str], personal: bool) {
+	match json {
+		serde_json::Value::String(val) if personal => *val = REDACTED.into(),
+		serde_json::Value::Array(vals) => {
+			for val in vals {
+				redact_json(val, keys, personal);
+			}
+		}
+		serde_json::Value::Object(map) => {
+			for (key, val) in map.iter_mut() {
+				redact_json(val, keys, personal || keys.contains(&key.as_str()));
+			}
+		}
+		_ => {}
+	}
+}
+
+fn cassette_error(path: &Path, msg: &str) -> KretaError {
+	KretaError::Cassette(format!("{}: {msg}", path.display()))
+}
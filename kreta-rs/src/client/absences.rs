@@ -1,45 +1,30 @@
 #[cfg(feature = "timerange")]
 #[cfg(feature = "client")]
-use chrono::Utc;
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "client")]
 use crate::client::Client;
 use crate::client::exam::{ClassGroupRaw, SubjectRaw, UidNameAndDescRaw};
+#[cfg(feature = "client")]
+use crate::error::{KretaError, from_json};
 
 #[cfg(feature = "client")]
 impl Client {
 	/// https://nzx.hu/kreta-api/mobileapi/getomissions \
 	/// from & to are both yyyy-mm-dd
 	/// maximum distance is 3 weeks
-	pub async fn absences(&self, from: &str, to: &str) -> anyhow::Result<Vec<AbsenceRaw>> {
+	pub async fn absences(&self, from: &str, to: &str) -> Result<Vec<AbsenceRaw>, KretaError> {
 		// println!("fetching absences between {from} and {to}");
 
-		use anyhow::Context;
-
 		let url = format!(
-			"https://{}.e-kreta.hu/ellenorzo/v3/sajat/Mulasztasok?datumTol={from}&datumIg={to}",
-			self.inst_id
+			"{}/ellenorzo/v3/sajat/Mulasztasok?datumTol={from}&datumIg={to}",
+			self.api_url()
 		);
-		let req = self
-			.client
-			.get(&url)
-			.bearer_auth(self.access_token())
-			.build()?;
-
-		let resp = self.client.execute(req).await?;
-		let status_code = resp.status();
-		if !status_code.is_success() {
-			use anyhow::anyhow;
-
-			let body = resp.text().await?;
-			let err = anyhow!("{url} returned {status_code}\n{body}");
-			return Err(err);
-		}
+		let resp = self.get(&url).await?;
 
 		let resp = resp.text().await?;
-		let resp: Vec<AbsenceRaw> = serde_json::from_str(&resp)
-			.with_context(|| format!("failed to deserialize response from {url}"))?;
+		let resp: Vec<AbsenceRaw> = from_json(&url, &resp)?;
 
 		Ok(resp)
 	}
@@ -48,52 +33,43 @@ impl Client {
 #[cfg(feature = "client")]
 #[cfg(feature = "timerange")]
 impl Client {
-	/// creates a pollable stream of the absences in chunks
-	/// needs to be polled to start doing anything
+	/// sets up a new FuturesUnordered with all of the chunks inside but doesn't start polling yet
+	/// so no need for this function to be async \
+	/// `range` is half-open, see [range](super::range). chunks may arrive in any order
 	pub fn absences_range_stream(
 		&self,
-		from: chrono::DateTime<Utc>,
-		to: chrono::DateTime<Utc>,
+		range: std::ops::Range<chrono::NaiveDate>,
 	) -> impl futures::Stream<Item = anyhow::Result<Vec<AbsenceRaw>>> {
 		use futures::stream::FuturesUnordered;
 
-		let timesplit = timerange::range(
-			from,
-			to,
-			chrono::Duration::weeks(3),
-			chrono::Duration::days(1),
-		);
+		let chunks = super::range::query_chunks(range, 21, super::range::RangeEnd::Inclusive);
 
 		let mut stream = FuturesUnordered::new();
-		stream.extend(timesplit.map(|(from, to)| async move {
-			let from = from.format("%Y-%m-%d").to_string();
-			let to = to.format("%Y-%m-%d").to_string();
-
-			let absences = self.absences(&from, &to).await?;
-			anyhow::Ok(absences)
+		stream.extend(chunks.map(|(from, to)| async move {
+			let items = self.absences(&from, &to).await?;
+			anyhow::Ok(items)
 		}));
 
 		stream
 	}
 
-	/// absence query with no maximum distance between from & to
+	/// absences query with no maximum distance between the start & end of `range` (half-open) \
+	/// everything is returned once, even if kreta sends it in more than one chunk
 	pub async fn absences_range(
 		&self,
-		from: chrono::DateTime<Utc>,
-		to: chrono::DateTime<Utc>,
+		range: std::ops::Range<chrono::NaiveDate>,
 	) -> anyhow::Result<Vec<AbsenceRaw>> {
-		use futures::StreamExt as _;
+		use futures::StreamExt;
 
 		let mut buf = Vec::new();
 
-		let mut stream = self.absences_range_stream(from, to);
+		let mut stream = self.absences_range_stream(range);
 		while let Some(next) = stream.next().await {
-			use anyhow::Context;
-
-			let next =
-				next.with_context(|| format!("while reading absences from timerange stream"))?;
+			let next = next.with_context(|| "while reading absences from timerange stream")?;
 			buf.extend(next);
 		}
+		super::range::dedup_by_uid(&mut buf, |item| &item.uid);
+
 		Ok(buf)
 	}
 }
@@ -0,0 +1,218 @@
+use anyhow::{Context, anyhow};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use super::Client;
+#[cfg(feature = "client")]
+use crate::error::{KretaError, from_json};
+
+#[cfg(feature = "client")]
+impl Client {
+	/// https://nzx.hu/kreta-api/mobileapi/getschoolyearcalendar \
+	/// the official calendar of the current school year: holidays, non-teaching days, term boundaries, etc
+	pub async fn school_year_calendar(&self) -> Result<Vec<SchoolYearDayRaw>, KretaError> {
+		let url = format!("{}/ellenorzo/v3/sajat/TanevRendjeElemek", self.api_url());
+		let resp = self.get(&url).await?;
+
+		let resp = resp.text().await?;
+		let resp: Vec<SchoolYearDayRaw> = from_json(&url, &resp)?;
+
+		Ok(resp)
+	}
+}
+
+#[cfg(feature = "client")]
+#[cfg(feature = "timerange")]
+impl Client {
+	/// the current school year according to [Client::school_year_calendar], from its first day up to and including its last,
+	/// ready to be passed to any of the *_range functions
+	pub async fn school_year_range(&self) -> anyhow::Result<std::ops::Range<chrono::NaiveDate>> {
+		let calendar = self
+			.school_year_calendar()
+			.await
+			.with_context(|| "while querying the school year calendar")?;
+		let (from, to) = school_year_dates(&calendar)?;
+
+		Ok(inclusive_to_range(from, to))
+	}
+
+	/// the current term (felev) according to [Client::school_year_calendar], from its first day up to and including its last,
+	/// ready to be passed to any of the *_range functions
+	pub async fn current_term_range(&self) -> anyhow::Result<std::ops::Range<chrono::NaiveDate>> {
+		let calendar = self
+			.school_year_calendar()
+			.await
+			.with_context(|| "while querying the school year calendar")?;
+
+		let today = chrono::Utc::now()
+			.with_timezone(&chrono_tz::Europe::Budapest)
+			.date_naive();
+		let (from, to) = current_term_dates(&calendar, today)?;
+
+		Ok(inclusive_to_range(from, to))
+	}
+}
+
+#[cfg(feature = "client")]
+#[cfg(feature = "timerange")]
+/// the *_range functions take half-open ranges, the calendar gives us the last day
+fn inclusive_to_range(
+	first: chrono::NaiveDate,
+	last: chrono::NaiveDate,
+) -> std::ops::Range<chrono::NaiveDate> {
+	first..last + chrono::Duration::days(1)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// a single entry of the school year's calendar, as returned by https://[instituteCode].e-kreta.hu/ellenorzo/v3/sajat/TanevRendjeElemek
+pub struct SchoolYearDayRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+	#[serde(rename = "Datum")]
+	pub date: String,
+
+	#[serde(rename = "Naptipus")]
+	/// see [SchoolYearDayRaw::kind]
+	pub day_type: UidNameAndDescRaw,
+	#[serde(rename = "ElteroOrarendSzerintiTanitasiNap")]
+	/// set if the day follows a different day's timetable (like a saturday with a monday timetable)
+	pub different_timetable_day: Option<UidNameAndDescRaw>,
+	#[serde(rename = "OrarendiNapHetirendje")]
+	/// a het, b het, etc
+	pub week_order: Option<UidNameAndDescRaw>,
+
+	#[serde(rename = "OsztalyCsoport")]
+	/// None if the entry applies to the whole school
+	pub class_group: Option<ClassGroupRaw>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// derived from [SchoolYearDayRaw::day_type]
+pub enum DayKind {
+	TeachingDay,
+	FirstTeachingDay,
+	LastTeachingDay,
+	/// the last day of the first term (elso felev vege)
+	EndOfFirstTerm,
+	/// teaching day with shortened lessons
+	ShortenedDay,
+	/// tanitas nelkuli munkanap
+	NonTeachingWorkday,
+	/// tanitasi szunet
+	Holiday,
+	/// unnepnap
+	PublicHoliday,
+	Other,
+}
+impl DayKind {
+	pub fn from_name(name: &str) -> Self {
+		let name = name.to_lowercase().replace('_', "");
+
+		if name.contains("elsotanitasinap") {
+			Self::FirstTeachingDay
+		} else if name.contains("utolsotanitasinap") {
+			Self::LastTeachingDay
+		} else if name.contains("felevvege") {
+			Self::EndOfFirstTerm
+		} else if name.contains("rovidit") {
+			Self::ShortenedDay
+		} else if name.contains("tanitasnelkuli") {
+			Self::NonTeachingWorkday
+		} else if name.contains("szunet") {
+			Self::Holiday
+		} else if name.contains("unnepnap") {
+			Self::PublicHoliday
+		} else if name.contains("tanitasinap") {
+			Self::TeachingDay
+		} else {
+			Self::Other
+		}
+	}
+
+	/// whether there are no lessons on this day
+	pub fn is_day_off(self) -> bool {
+		matches!(
+			self,
+			Self::NonTeachingWorkday | Self::Holiday | Self::PublicHoliday
+		)
+	}
+}
+
+impl SchoolYearDayRaw {
+	pub fn kind(&self) -> DayKind {
+		DayKind::from_name(&self.day_type.name)
+	}
+}
+
+#[cfg(feature = "timerange")]
+impl SchoolYearDayRaw {
+	/// the day this entry is about, in hungarian time
+	pub fn date_naive(&self) -> anyhow::Result<chrono::NaiveDate> {
+		let date: chrono::DateTime<chrono::Utc> = self
+			.date
+			.parse()
+			.with_context(|| format!("failed to parse {} as DateTime<Utc>", self.date))?;
+		let date = date
+			.with_timezone(&chrono_tz::Europe::Budapest)
+			.date_naive();
+		Ok(date)
+	}
+}
+
+#[cfg(feature = "timerange")]
+fn dates_of_kind(
+	days: &[SchoolYearDayRaw],
+	kind: DayKind,
+) -> anyhow::Result<Vec<chrono::NaiveDate>> {
+	days.iter()
+		.filter(|day| day.kind() == kind)
+		.map(SchoolYearDayRaw::date_naive)
+		.collect()
+}
+
+#[cfg(feature = "timerange")]
+/// first and last teaching day of the school year. falls back to the first and last entry if kreta doesn't mark them explicitly
+pub fn school_year_dates(
+	days: &[SchoolYearDayRaw],
+) -> anyhow::Result<(chrono::NaiveDate, chrono::NaiveDate)> {
+	let all = days
+		.iter()
+		.map(SchoolYearDayRaw::date_naive)
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	let first = dates_of_kind(days, DayKind::FirstTeachingDay)?
+		.into_iter()
+		.min()
+		.or_else(|| all.iter().copied().min());
+	let last = dates_of_kind(days, DayKind::LastTeachingDay)?
+		.into_iter()
+		.max()
+		.or_else(|| all.iter().copied().max());
+
+	match (first, last) {
+		(Some(first), Some(last)) => Ok((first, last)),
+		_ => Err(anyhow!("the school year calendar is empty")),
+	}
+}
+
+#[cfg(feature = "timerange")]
+/// the term `today` is in. the first term ends on [DayKind::EndOfFirstTerm], the second one starts the day after \
+/// if kreta doesn't tell us when the first term ends, the whole school year is returned
+pub fn current_term_dates(
+	days: &[SchoolYearDayRaw],
+	today: chrono::NaiveDate,
+) -> anyhow::Result<(chrono::NaiveDate, chrono::NaiveDate)> {
+	let (first, last) = school_year_dates(days)?;
+	let end_of_first_term = dates_of_kind(days, DayKind::EndOfFirstTerm)?
+		.into_iter()
+		.max();
+
+	match end_of_first_term {
+		Some(end) if today <= end => Ok((first, end)),
+		Some(end) => Ok((end + chrono::Duration::days(1), last)),
+		None => Ok((first, last)),
+	}
+}
+
+pub use crate::client::homework::ClassGroupRaw;
+pub use crate::client::timetable::UidNameAndDescRaw;
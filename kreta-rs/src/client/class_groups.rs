@@ -0,0 +1,136 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use super::Client;
+#[cfg(feature = "client")]
+use crate::error::{KretaError, from_json};
+
+#[cfg(feature = "client")]
+impl Client {
+	/// https://nzx.hu/kreta-api/mobileapi/getclassgroups \
+	/// every class and group the student is (or was) a member of
+	pub async fn class_groups(&self) -> Result<Vec<ClassGroupDetailsRaw>, KretaError> {
+		let url = format!("{}/ellenorzo/v3/sajat/OsztalyCsoportok", self.api_url());
+		let resp = self.get(&url).await?;
+
+		let resp = resp.text().await?;
+		let resp: Vec<ClassGroupDetailsRaw> = from_json(&url, &resp)?;
+
+		Ok(resp)
+	}
+
+	/// https://nzx.hu/kreta-api/mobileapi/getclassaverages \
+	/// the student's and the class' averages per subject \
+	/// kreta doesn't take the group's uid here, but the uid of its education task,
+	/// see [ClassGroupDetailsRaw::education_task] or just use [Client::class_averages_of]
+	pub async fn class_averages(
+		&self,
+		education_task_uid: &str,
+	) -> Result<Vec<ClassAverageRaw>, KretaError> {
+		let url = format!(
+			"{}/ellenorzo/v3/sajat/Ertekelesek/Atlagok/OsztalyAtlagok?oktatasiNevelesiFeladatUid={education_task_uid}",
+			self.api_url()
+		);
+		let resp = self.get(&url).await?;
+
+		let resp = resp.text().await?;
+		let resp: Vec<ClassAverageRaw> = from_json(&url, &resp)?;
+
+		Ok(resp)
+	}
+
+	/// [Client::class_averages] for the given group
+	pub async fn class_averages_of(
+		&self,
+		group: &ClassGroupDetailsRaw,
+	) -> anyhow::Result<Vec<ClassAverageRaw>> {
+		let task = group.education_task.as_ref().ok_or_else(|| {
+			anyhow!(
+				"class group {} ({}) doesn't have an education task, can't query its averages",
+				group.name,
+				group.uid
+			)
+		})?;
+		Ok(self.class_averages(&task.uid).await?)
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// as returned by https://[instituteCode].e-kreta.hu/ellenorzo/v3/sajat/OsztalyCsoportok \
+/// not to be confused with [ClassGroupRaw], which is how other endpoints refer to a class group
+pub struct ClassGroupDetailsRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+	#[serde(rename = "Nev")]
+	pub name: String,
+
+	#[serde(rename = "Tipus")]
+	/// "Osztaly" for the student's class, "Csoport" for groups
+	pub typ: String,
+	#[serde(rename = "IsAktiv")]
+	pub is_active: bool,
+
+	#[serde(rename = "OktatasNevelesiFeladat")]
+	pub education_task: Option<UidNameAndDescRaw>,
+	#[serde(rename = "OktatasNevelesiKategoria")]
+	pub education_category: Option<UidNameAndDescRaw>,
+
+	#[serde(rename = "OsztalyFonok")]
+	pub head_teacher: Option<UidRaw>,
+	#[serde(rename = "OsztalyFonokHelyettes")]
+	pub deputy_head_teacher: Option<UidRaw>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UidRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+}
+
+impl ClassGroupDetailsRaw {
+	/// whether this is the student's actual class (osztaly) rather than a group
+	pub fn is_class(&self) -> bool {
+		self.typ == "Osztaly"
+	}
+}
+
+/// the student's current class (like 10.A), if kreta tells us
+pub fn current_class(groups: &[ClassGroupDetailsRaw]) -> Option<&ClassGroupDetailsRaw> {
+	groups
+		.iter()
+		.find(|group| group.is_class() && group.is_active)
+}
+
+/// keeps the lessons that either belong to one of `group_uids` or don't belong to any group
+pub fn filter_lessons_by_groups<'a>(
+	lessons: impl IntoIterator<Item = &'a LessonRaw>,
+	group_uids: &'a [&'a str],
+) -> impl Iterator<Item = &'a LessonRaw> {
+	lessons
+		.into_iter()
+		.filter(|lesson| match &lesson.class_group {
+			Some(group) => group_uids.contains(&group.uid.as_str()),
+			None => true,
+		})
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// as returned by https://[instituteCode].e-kreta.hu/ellenorzo/v3/sajat/Ertekelesek/Atlagok/OsztalyAtlagok
+pub struct ClassAverageRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+	#[serde(rename = "Tantargy")]
+	pub subject: SubjectRaw,
+
+	#[serde(rename = "TanuloAtlag")]
+	pub student_average: Option<f32>,
+	#[serde(rename = "OsztalyCsoportAtlag")]
+	pub class_average: Option<f32>,
+	#[serde(rename = "OsztalyCsoportAtlagtolValoElteres")]
+	/// student average - class average
+	pub difference: Option<f32>,
+}
+
+pub use crate::client::homework::ClassGroupRaw;
+pub use crate::client::timetable::{LessonRaw, SubjectRaw, UidNameAndDescRaw};
@@ -1,58 +1,143 @@
-use std::time::{Duration, Instant};
+use std::{
+	sync::RwLock,
+	time::{Duration, Instant, SystemTime},
+};
 
 use anyhow::Context;
 
-use crate::login::{Credentials, LoginFlow, TokensRaw};
+use crate::{
+	config::ClientConfig,
+	error::{KretaError, error_for_status},
+	limit::Limiter,
+	login::{Credentials, LoginFlow, Session, TokensRaw},
+};
 
 /// the main client interface with which you can interact with the kreta api. \
-/// an instance of this type has already logged in, but does not guarantee that the access token hasn't expired
+/// an instance of this type has already logged in. the access token is refreshed automatically when it expires,
+/// so a single `&Client` can be shared between any number of tasks
 pub struct Client {
 	pub(crate) client: reqwest::Client,
+	pub(crate) config: ClientConfig,
 
 	pub(crate) inst_id: String,
-	pub(crate) tokens: TokensRaw,
+	pub(crate) tokens: RwLock<TokenState>,
+	/// held while refreshing, so concurrent requests with an expired token only refresh it once
+	pub(crate) refresh_lock: futures::lock::Mutex<()>,
+	/// [ClientConfig::max_concurrent_requests] and [ClientConfig::rate_limit] of this client
+	pub(crate) limiter: Limiter,
+}
 
+#[derive(Clone, Debug)]
+pub(crate) struct TokenState {
+	pub(crate) tokens: TokensRaw,
 	pub(crate) access_expires: Instant,
 }
+impl TokenState {
+	pub(crate) fn new(tokens: TokensRaw) -> Self {
+		let access_expires = Instant::now() + Duration::from_secs(tokens.expires_in.abs() as _);
+		Self {
+			tokens,
+			access_expires,
+		}
+	}
+}
+
+impl Client {
+	/// the current tokens of this client, to be restored later with [Client::from_session]
+	pub fn session(&self) -> Session {
+		let state = self.token_state();
+		let valid_for = state
+			.access_expires
+			.saturating_duration_since(Instant::now());
+
+		Session::new(
+			self.inst_id.clone(),
+			state.tokens,
+			SystemTime::now() + valid_for,
+		)
+	}
+
+	/// resumes a session saved with [Client::session] without logging in again \
+	/// if the access token has expired since, it's refreshed on the first request.
+	/// if the refresh token has expired too, that request fails with [KretaError::Unauthorized] and you'll have to log in again
+	pub fn from_session(session: Session) -> Result<Self, KretaError> {
+		Self::from_session_with(session, ClientConfig::default())
+	}
+
+	/// [Client::from_session] with a custom config
+	pub fn from_session_with(session: Session, config: ClientConfig) -> Result<Self, KretaError> {
+		config.redact(&session.inst_id);
+		let access_expires = Instant::now() + session.access_valid_for();
+		let state = TokenState {
+			tokens: session.tokens,
+			access_expires,
+		};
+
+		Ok(Self {
+			client: config.http_client()?,
+			limiter: Limiter::new(&config),
+			config,
+			inst_id: session.inst_id,
+			tokens: RwLock::new(state),
+			refresh_lock: Default::default(),
+		})
+	}
+}
 
 impl Client {
 	/// refresh_if_needed won't work as expected if you wait a lot between getting your tokens and calling Client::new \
 	/// you should probably use [Client::full_login] anyways
 	pub fn new(client: reqwest::Client, inst_id: String, tokens: TokensRaw) -> Self {
-		let access_expires = Instant::now() + Duration::from_secs(tokens.expires_in.abs() as _);
-
+		Self::new_with_config(client, ClientConfig::default(), inst_id, tokens)
+	}
+	/// [Client::new] but the urls and such come from `config` instead of the defaults
+	pub fn new_with_config(
+		client: reqwest::Client,
+		config: ClientConfig,
+		inst_id: String,
+		tokens: TokensRaw,
+	) -> Self {
+		config.redact(&inst_id);
 		Self {
 			client,
+			limiter: Limiter::new(&config),
+			config,
 			inst_id,
-			tokens,
-			access_expires,
+			tokens: RwLock::new(TokenState::new(tokens)),
+			refresh_lock: Default::default(),
 		}
 	}
 
 	/// completes the entire login sequence using [LoginFlow]
 	pub async fn full_login(credentials: &Credentials) -> anyhow::Result<Self> {
+		Self::full_login_with(credentials, ClientConfig::default()).await
+	}
+
+	/// [Client::full_login] with a custom config, like one pointing to a mock server
+	pub async fn full_login_with(
+		credentials: &Credentials,
+		config: ClientConfig,
+	) -> anyhow::Result<Self> {
 		println!("performing full login for {}", credentials.username());
-		async fn internal(credentials: &Credentials) -> anyhow::Result<Client> {
-			let login_flow = LoginFlow::new()?;
+		async fn internal(
+			credentials: &Credentials,
+			config: ClientConfig,
+		) -> anyhow::Result<Client> {
+			let login_flow = LoginFlow::with_config(config.clone())?;
 
 			let data = login_flow.begin().await?;
 			login_flow.post_credentials(&data, credentials).await?;
 
 			let tokens = login_flow.request_token(&data).await?;
-			let access_expires = Instant::now() + Duration::from_secs(tokens.expires_in.abs() as _);
 
 			let client = login_flow.take_client();
-			let client = Client {
-				client,
-				inst_id: credentials.inst_id().into(),
-				tokens,
-				access_expires,
-			};
+			let client =
+				Client::new_with_config(client, config, credentials.inst_id().into(), tokens);
 
 			Ok(client)
 		}
 
-		internal(&credentials).await.with_context(|| {
+		internal(credentials, config).await.with_context(|| {
 			format!(
 				"while logging in with credentials for {}",
 				credentials.username()
@@ -62,10 +147,83 @@ impl Client {
 }
 
 impl Client {
-	pub(crate) fn access_token(&self) -> &str {
-		&self.tokens.access_token
+	pub(crate) fn token_state(&self) -> TokenState {
+		self.tokens.read().expect("token lock poisoned").clone()
+	}
+	pub(crate) fn set_tokens(&self, tokens: TokensRaw) {
+		*self.tokens.write().expect("token lock poisoned") = TokenState::new(tokens);
+	}
+
+	pub(crate) fn access_token(&self) -> String {
+		self.token_state().tokens.access_token
 	}
 	pub fn inst_id(&self) -> &str {
 		&self.inst_id
 	}
+	pub fn config(&self) -> &ClientConfig {
+		&self.config
+	}
+	/// the base url of the ellenorzo api for this client's school, without a trailing slash
+	pub(crate) fn api_url(&self) -> String {
+		self.config.api_url(&self.inst_id)
+	}
+}
+
+impl Client {
+	/// sends a request to kreta with our access token, refreshing it first if it has expired \
+	/// if kreta still answers with 401 (the token got revoked, our clock is off, etc), refreshes and retries once \
+	/// 429 and 5xx are retried as [ClientConfig::backoff] says
+	pub(crate) async fn send(
+		&self,
+		url: &str,
+		request: impl Fn(&reqwest::Client) -> reqwest::RequestBuilder,
+	) -> Result<reqwest::Response, KretaError> {
+		// only built to see what kind of request it is, it's never sent
+		let idempotent = request(&self.client).build()?.method().is_idempotent();
+
+		let mut attempt = 0;
+		loop {
+			let resp = self.send_authorized(&request).await?;
+			match self.config.backoff.delay(attempt, idempotent, &resp) {
+				Some(delay) => {
+					attempt += 1;
+					tokio::time::sleep(delay).await;
+				}
+				None => return error_for_status(url, resp).await,
+			}
+		}
+	}
+
+	/// the 401 handling of [Client::send]
+	async fn send_authorized(
+		&self,
+		request: &impl Fn(&reqwest::Client) -> reqwest::RequestBuilder,
+	) -> Result<reqwest::Response, KretaError> {
+		self.refresh_if_needed().await?;
+
+		let token = self.access_token();
+		let req = request(&self.client).bearer_auth(&token).build()?;
+		let resp = self.execute(req).await?;
+		if resp.status() != reqwest::StatusCode::UNAUTHORIZED {
+			return Ok(resp);
+		}
+
+		self.refresh_rejected(&token).await?;
+
+		let req = request(&self.client)
+			.bearer_auth(self.access_token())
+			.build()?;
+		self.execute(req).await
+	}
+
+	/// [ClientConfig::execute], once the limits of this client let the request through
+	async fn execute(&self, req: reqwest::Request) -> Result<reqwest::Response, KretaError> {
+		let _permit = self.limiter.acquire().await;
+		self.config.execute(&self.client, req).await
+	}
+
+	/// [Client::send] with a get request
+	pub(crate) async fn get(&self, url: &str) -> Result<reqwest::Response, KretaError> {
+		self.send(url, |client| client.get(url)).await
+	}
 }
@@ -0,0 +1,177 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use super::Client;
+#[cfg(feature = "client")]
+use crate::error::{KretaError, from_json};
+
+#[cfg(feature = "client")]
+impl Client {
+	/// https://nzx.hu/kreta-api/mobileapi/getconsultinghours \
+	/// parent-teacher consultation hours (fogadoorak) \
+	/// from & to are yyyy-mm-dd
+	pub async fn consultation_hours(
+		&self,
+		from: &str,
+		to: &str,
+	) -> Result<Vec<ConsultationHourRaw>, KretaError> {
+		let url = format!(
+			"{}/ellenorzo/v3/sajat/Fogadoorak?datumTol={from}&datumIg={to}",
+			self.api_url()
+		);
+		let resp = self.get(&url).await?;
+
+		let resp = resp.text().await?;
+		let resp: Vec<ConsultationHourRaw> = from_json(&url, &resp)?;
+
+		Ok(resp)
+	}
+
+	/// books the given slot of a consultation hour \
+	/// only works if [ConsultationHourRaw::is_booking_enabled] and the slot is free
+	pub async fn book_consultation_slot(
+		&self,
+		slot: &ConsultationSlotRaw,
+	) -> Result<(), KretaError> {
+		self.consultation_slot_request(reqwest::Method::POST, slot)
+			.await
+	}
+
+	/// cancels a previous booking of the given slot
+	pub async fn cancel_consultation_slot(
+		&self,
+		slot: &ConsultationSlotRaw,
+	) -> Result<(), KretaError> {
+		self.consultation_slot_request(reqwest::Method::DELETE, slot)
+			.await
+	}
+
+	async fn consultation_slot_request(
+		&self,
+		method: reqwest::Method,
+		slot: &ConsultationSlotRaw,
+	) -> Result<(), KretaError> {
+		let url = format!(
+			"{}/ellenorzo/v3/sajat/Fogadoorak/Idopontok/Jelentkezesek/{}",
+			self.api_url(),
+			slot.uid
+		);
+		self.send(&url, |client| client.request(method.clone(), &url))
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[cfg(feature = "client")]
+#[cfg(feature = "timerange")]
+impl Client {
+	/// sets up a new FuturesUnordered with all of the chunks inside but doesn't start polling yet
+	/// so no need for this function to be async \
+	/// `range` is half-open, see [range](super::range). chunks may arrive in any order
+	pub fn consultation_hours_range_stream(
+		&self,
+		range: std::ops::Range<chrono::NaiveDate>,
+	) -> impl futures::Stream<Item = anyhow::Result<Vec<ConsultationHourRaw>>> {
+		use futures::stream::FuturesUnordered;
+
+		let chunks = super::range::query_chunks(range, 30, super::range::RangeEnd::Inclusive);
+
+		let mut stream = FuturesUnordered::new();
+		stream.extend(chunks.map(|(from, to)| async move {
+			let items = self.consultation_hours(&from, &to).await?;
+			anyhow::Ok(items)
+		}));
+
+		stream
+	}
+
+	/// consultation hours query with no maximum distance between the start & end of `range` (half-open) \
+	/// everything is returned once, even if kreta sends it in more than one chunk
+	pub async fn consultation_hours_range(
+		&self,
+		range: std::ops::Range<chrono::NaiveDate>,
+	) -> anyhow::Result<Vec<ConsultationHourRaw>> {
+		use futures::StreamExt;
+
+		let mut buf = Vec::new();
+
+		let mut stream = self.consultation_hours_range_stream(range);
+		while let Some(next) = stream.next().await {
+			let next =
+				next.with_context(|| "while reading consultation hours from timerange stream")?;
+			buf.extend(next);
+		}
+		super::range::dedup_by_uid(&mut buf, |item| &item.uid);
+
+		Ok(buf)
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// as returned by https://[instituteCode].e-kreta.hu/ellenorzo/v3/sajat/Fogadoorak
+pub struct ConsultationHourRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+
+	#[serde(rename = "Tanar")]
+	pub teacher: TeacherRaw,
+	#[serde(rename = "Terem")]
+	pub classroom: Option<String>,
+
+	#[serde(rename = "KezdoIdopont")]
+	pub start: String,
+	#[serde(rename = "VegIdopont")]
+	pub end: String,
+
+	#[serde(rename = "IsJelentkezesFeatureEnabled")]
+	/// whether the slots can be booked through the api
+	pub is_booking_enabled: bool,
+	#[serde(rename = "JelentkezesHatarido")]
+	/// slots can't be booked or cancelled after this
+	pub booking_deadline: Option<String>,
+
+	#[serde(rename = "Idopontok")]
+	#[serde(default)]
+	pub slots: Vec<ConsultationSlotRaw>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TeacherRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+	#[serde(rename = "Nev")]
+	pub name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// a single bookable part of a [ConsultationHourRaw]
+pub struct ConsultationSlotRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+	#[serde(rename = "KezdoIdopont")]
+	pub start: String,
+	#[serde(rename = "VegIdopont")]
+	pub end: String,
+
+	#[serde(rename = "IsJelentkeztem")]
+	/// whether the logged in student has booked this slot
+	pub is_booked_by_me: bool,
+	#[serde(rename = "IsSzabad")]
+	pub is_free: bool,
+}
+
+impl ConsultationHourRaw {
+	/// the slots that can currently be booked
+	pub fn free_slots(&self) -> impl Iterator<Item = &ConsultationSlotRaw> {
+		self.slots
+			.iter()
+			.filter(move |slot| self.is_booking_enabled && slot.is_free)
+	}
+
+	/// the slot booked by the logged in student, if any
+	pub fn my_slot(&self) -> Option<&ConsultationSlotRaw> {
+		self.slots.iter().find(|slot| slot.is_booked_by_me)
+	}
+}
@@ -1,37 +1,26 @@
-use anyhow::{Context, anyhow};
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "client")]
 use super::Client;
+#[cfg(feature = "client")]
+use crate::error::{KretaError, from_json};
 
 #[cfg(feature = "client")]
 impl Client {
 	/// https://nzx.hu/kreta-api/mobileapi/getannouncedtests \
 	/// from & to are yyyy-mm-dd
 	/// max distance is 1 month
-	pub async fn exams(&self, from: &str, to: &str) -> anyhow::Result<Vec<ExamRaw>> {
+	pub async fn exams(&self, from: &str, to: &str) -> Result<Vec<ExamRaw>, KretaError> {
 		let url = format!(
-			"https://{}.e-kreta.hu/ellenorzo/v3/sajat/BejelentettSzamonkeresek?datumTol={from}&datumIg={to}",
-			self.inst_id
+			"{}/ellenorzo/v3/sajat/BejelentettSzamonkeresek?datumTol={from}&datumIg={to}",
+			self.api_url()
 		);
-		let req = self
-			.client
-			.get(&url)
-			.bearer_auth(self.access_token())
-			.build()?;
-
-		let resp = self.client.execute(req).await?;
-		let status_code = resp.status();
-		if !status_code.is_success() {
-			let body = resp.text().await?;
-			let err = anyhow!("{url} returned {status_code}\n{body}");
-			return Err(err);
-		}
+		let resp = self.get(&url).await?;
 
 		let resp = resp.text().await?;
 		// println!("{resp}");
-		let resp: Vec<ExamRaw> = serde_json::from_str(&resp)
-			.with_context(|| format!("failed to deserialize response from {url}"))?;
+		let resp: Vec<ExamRaw> = from_json(&url, &resp)?;
 
 		Ok(resp)
 	}
@@ -41,48 +30,41 @@ impl Client {
 #[cfg(feature = "timerange")]
 impl Client {
 	/// sets up a new FuturesUnordered with all of the chunks inside but doesn't start polling yet
-	/// so no need for this function to be async
+	/// so no need for this function to be async \
+	/// `range` is half-open, see [range](super::range). chunks may arrive in any order
 	pub fn exams_range_stream(
 		&self,
-		from: chrono::DateTime<chrono::Utc>,
-		to: chrono::DateTime<chrono::Utc>,
+		range: std::ops::Range<chrono::NaiveDate>,
 	) -> impl futures::Stream<Item = anyhow::Result<Vec<ExamRaw>>> {
 		use futures::stream::FuturesUnordered;
 
-		let timesplit = timerange::range(
-			from,
-			to,
-			chrono::Duration::days(30),
-			chrono::Duration::days(1),
-		);
+		let chunks = super::range::query_chunks(range, 30, super::range::RangeEnd::Inclusive);
 
 		let mut stream = FuturesUnordered::new();
-		stream.extend(timesplit.map(|(from, to)| async move {
-			let from = from.format("%Y-%m-%d").to_string();
-			let to = to.format("%Y-%m-%d").to_string();
-
-			let exams = self.exams(&from, &to).await?;
-			anyhow::Ok(exams)
+		stream.extend(chunks.map(|(from, to)| async move {
+			let items = self.exams(&from, &to).await?;
+			anyhow::Ok(items)
 		}));
 
 		stream
 	}
 
-	/// exams query with no maximum distance between from & to
+	/// exams query with no maximum distance between the start & end of `range` (half-open) \
+	/// everything is returned once, even if kreta sends it in more than one chunk
 	pub async fn exams_range(
 		&self,
-		from: chrono::DateTime<chrono::Utc>,
-		to: chrono::DateTime<chrono::Utc>,
+		range: std::ops::Range<chrono::NaiveDate>,
 	) -> anyhow::Result<Vec<ExamRaw>> {
 		use futures::StreamExt;
 
 		let mut buf = Vec::new();
 
-		let mut stream = self.exams_range_stream(from, to);
+		let mut stream = self.exams_range_stream(range);
 		while let Some(next) = stream.next().await {
 			let next = next.with_context(|| "while reading exams from timerange stream")?;
 			buf.extend(next);
 		}
+		super::range::dedup_by_uid(&mut buf, |item| &item.uid);
 
 		Ok(buf)
 	}
@@ -0,0 +1,175 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use super::Client;
+#[cfg(feature = "client")]
+use crate::error::{KretaError, from_json};
+
+#[cfg(feature = "client")]
+impl Client {
+	/// https://nzx.hu/kreta-api/mobileapi/getevaluations \
+	/// from & to are yyyy-mm-dd
+	/// max distance is 1 month
+	pub async fn grades(&self, from: &str, to: &str) -> Result<Vec<GradeRaw>, KretaError> {
+		let url = format!(
+			"{}/ellenorzo/v3/sajat/Ertekelesek?datumTol={from}&datumIg={to}",
+			self.api_url()
+		);
+		let resp = self.get(&url).await?;
+
+		let resp = resp.text().await?;
+		let resp: Vec<GradeRaw> = from_json(&url, &resp)?;
+
+		Ok(resp)
+	}
+}
+
+#[cfg(feature = "client")]
+#[cfg(feature = "timerange")]
+impl Client {
+	/// sets up a new FuturesUnordered with all of the chunks inside but doesn't start polling yet
+	/// so no need for this function to be async \
+	/// `range` is half-open, see [range](super::range). chunks may arrive in any order
+	pub fn grades_range_stream(
+		&self,
+		range: std::ops::Range<chrono::NaiveDate>,
+	) -> impl futures::Stream<Item = anyhow::Result<Vec<GradeRaw>>> {
+		use futures::stream::FuturesUnordered;
+
+		let chunks = super::range::query_chunks(range, 30, super::range::RangeEnd::Inclusive);
+
+		let mut stream = FuturesUnordered::new();
+		stream.extend(chunks.map(|(from, to)| async move {
+			let items = self.grades(&from, &to).await?;
+			anyhow::Ok(items)
+		}));
+
+		stream
+	}
+
+	/// grades query with no maximum distance between the start & end of `range` (half-open) \
+	/// everything is returned once, even if kreta sends it in more than one chunk
+	pub async fn grades_range(
+		&self,
+		range: std::ops::Range<chrono::NaiveDate>,
+	) -> anyhow::Result<Vec<GradeRaw>> {
+		use futures::StreamExt;
+
+		let mut buf = Vec::new();
+
+		let mut stream = self.grades_range_stream(range);
+		while let Some(next) = stream.next().await {
+			let next = next.with_context(|| "while reading grades from timerange stream")?;
+			buf.extend(next);
+		}
+		super::range::dedup_by_uid(&mut buf, |item| &item.uid);
+
+		Ok(buf)
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// as returned by https://[instituteCode].e-kreta.hu/ellenorzo/v3/sajat/Ertekelesek
+pub struct GradeRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+
+	#[serde(rename = "Tantargy")]
+	pub subject: SubjectRaw,
+	#[serde(rename = "Tema")]
+	pub topic: Option<String>,
+
+	#[serde(rename = "ErtekeloTanarNeve")]
+	pub teachers_name: Option<String>,
+
+	#[serde(rename = "SzamErtek")]
+	/// 1-5 for regular grades, 0 if the grade is textual or a percentage
+	pub value: i32,
+	#[serde(rename = "SzovegesErtek")]
+	/// the value as displayed by the official app, like "Jeles(5)"
+	pub value_text: String,
+	#[serde(rename = "SzovegesErtekelesRovidNev")]
+	pub value_text_short: Option<String>,
+	#[serde(rename = "SulySzazalekErteke")]
+	/// percentage, 100 is a normal grade, 200 is a double weight one (temazaro)
+	pub weight: Option<i32>,
+
+	#[serde(rename = "Tipus")]
+	/// see [GradeRaw::kind]
+	pub typ: UidNameAndDescRaw,
+	#[serde(rename = "Mod")]
+	/// irasbeli, szobeli, gyakorlati, etc
+	pub mode: Option<UidNameAndDescRaw>,
+	#[serde(rename = "ErtekFajta")]
+	/// osztalyzat, szazalekos, szoveges, etc
+	pub value_type: UidNameAndDescRaw,
+	#[serde(rename = "Jelleg")]
+	pub nature: String,
+
+	#[serde(rename = "KeszitesDatuma")]
+	/// the date the grade was given on
+	pub date: String,
+	#[serde(rename = "RogzitesDatuma")]
+	pub date_registered: String,
+	#[serde(rename = "LattamozasDatuma")]
+	pub date_seen: Option<String>,
+
+	#[serde(rename = "SortIndex")]
+	pub sort_index: i32,
+
+	#[serde(rename = "OsztalyCsoport")]
+	pub class_group: ClassGroupRaw,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// derived from [GradeRaw::typ]
+pub enum GradeKind {
+	/// a regular grade given during the year (evkozi jegy)
+	MidYear,
+	/// end of the first quarter (I. negyedeves)
+	FirstQuarter,
+	/// end of the first semester (felevi)
+	HalfYear,
+	/// end of the third quarter (III. negyedeves)
+	ThirdQuarter,
+	/// end of the year (evvegi)
+	EndOfYear,
+	/// something we haven't seen before
+	Other,
+}
+impl GradeKind {
+	pub fn from_name(name: &str) -> Self {
+		match name {
+			"evkozi_jegy_ertekeles" => Self::MidYear,
+			"I_ne_jegy_ertekeles" => Self::FirstQuarter,
+			"felevi_jegy_ertekeles" => Self::HalfYear,
+			"III_ne_jegy_ertekeles" => Self::ThirdQuarter,
+			"evvegi_jegy_ertekeles" => Self::EndOfYear,
+			_ => Self::Other,
+		}
+	}
+}
+
+impl GradeRaw {
+	pub fn kind(&self) -> GradeKind {
+		GradeKind::from_name(&self.typ.name)
+	}
+	/// the numeric value of the grade, if it has one that should count towards averages
+	pub fn numeric_value(&self) -> Option<i32> {
+		match self.value {
+			1..=5 => Some(self.value),
+			_ => None,
+		}
+	}
+	/// weight as a multiplier, 1.0 if kreta didn't give us one
+	pub fn weight_multiplier(&self) -> f32 {
+		match self.weight {
+			Some(weight) => weight as f32 / 100.0,
+			None => 1.0,
+		}
+	}
+}
+
+pub use crate::client::homework::ClassGroupRaw;
+pub use crate::client::timetable::{SubjectRaw, UidNameAndDescRaw};
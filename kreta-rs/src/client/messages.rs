@@ -0,0 +1,205 @@
+//! messages sent through kreta's e-ugyintezes (kommunikacio) system \
+//! unlike the rest of the api, these live on https://eugyintezes.e-kreta.hu and use camelCase
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use super::Client;
+#[cfg(feature = "client")]
+use crate::error::{KretaError, from_json};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Mailbox {
+	Inbox,
+	Sent,
+	Trash,
+}
+impl Mailbox {
+	pub fn as_path(self) -> &'static str {
+		match self {
+			Self::Inbox => "beerkezett",
+			Self::Sent => "elkuldott",
+			Self::Trash => "torolt",
+		}
+	}
+}
+
+#[cfg(feature = "client")]
+impl Client {
+	async fn eugyintezes_get<T: serde::de::DeserializeOwned>(
+		&self,
+		path: &str,
+	) -> Result<T, KretaError> {
+		let url = format!("{}{path}", self.config.eugyintezes_url);
+		let resp = self.get(&url).await?;
+
+		let resp = resp.text().await?;
+		let resp: T = from_json(&url, &resp)?;
+
+		Ok(resp)
+	}
+
+	/// every message in the given mailbox, without the actual text of the message (see [Client::message])
+	pub async fn messages(&self, mailbox: Mailbox) -> Result<Vec<MessageSummaryRaw>, KretaError> {
+		self.eugyintezes_get(&format!(
+			"/api/v1/kommunikacio/postaladaelemek/{}",
+			mailbox.as_path()
+		))
+		.await
+	}
+
+	/// the entire message with its text, recipients and attachments \
+	/// id is [MessageSummaryRaw::id], not [MessageSummaryRaw::message_id]
+	pub async fn message(&self, id: i64) -> Result<MessageRaw, KretaError> {
+		self.eugyintezes_get(&format!("/api/v1/kommunikacio/postaladaelemek/{id}"))
+			.await
+	}
+
+	/// marks the given messages as read (or unread) \
+	/// ids are [MessageSummaryRaw::id]s
+	pub async fn mark_messages_read(&self, ids: &[i64], read: bool) -> Result<(), KretaError> {
+		let url = format!(
+			"{}/api/v1/kommunikacio/uzenetek/olvasott",
+			self.config.eugyintezes_url
+		);
+		let body = MarkReadBody {
+			is_read: read,
+			ids: ids.into(),
+		};
+
+		self.send(&url, |client| client.post(&url).json(&body))
+			.await?;
+
+		Ok(())
+	}
+
+	/// downloads the contents of an attachment through the file service \
+	/// the name of the file is in [AttachmentRaw::file_name]
+	pub async fn attachment(&self, attachment: &AttachmentRaw) -> Result<Vec<u8>, KretaError> {
+		let url = format!(
+			"{}/api/v1/dokumentumok/uzenetek/{}",
+			self.config.eugyintezes_url, attachment.id
+		);
+		let resp = self.get(&url).await?;
+
+		let bytes = resp.bytes().await?;
+		Ok(bytes.into())
+	}
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct MarkReadBody {
+	#[serde(rename = "isOlvasott")]
+	is_read: bool,
+	#[serde(rename = "postaladaElemAzonositoLista")]
+	ids: Vec<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// as returned by https://eugyintezes.e-kreta.hu/api/v1/kommunikacio/postaladaelemek/[mailbox]
+pub struct MessageSummaryRaw {
+	#[serde(rename = "azonosito")]
+	/// the id of the message in this mailbox, use this with [Client::message](crate::client::Client::message)
+	pub id: i64,
+	#[serde(rename = "uzenetAzonosito")]
+	/// the id of the message itself, shared between the sender's and recipients' mailboxes
+	pub message_id: i64,
+
+	#[serde(rename = "uzenetKuldesDatum")]
+	pub date_sent: String,
+	#[serde(rename = "uzenetFeladoNev")]
+	pub senders_name: String,
+	#[serde(rename = "uzenetFeladoTitulus")]
+	pub senders_title: Option<String>,
+	#[serde(rename = "uzenetTargy")]
+	pub subject: String,
+
+	#[serde(rename = "hasCsatolmany")]
+	pub has_attachment: bool,
+	#[serde(rename = "isElolvasva")]
+	pub is_read: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// as returned by https://eugyintezes.e-kreta.hu/api/v1/kommunikacio/postaladaelemek/[id]
+pub struct MessageRaw {
+	#[serde(rename = "azonosito")]
+	pub id: i64,
+	#[serde(rename = "isElolvasva")]
+	pub is_read: bool,
+	#[serde(rename = "isToroltElem")]
+	pub is_deleted: bool,
+	#[serde(rename = "tipus")]
+	pub typ: Option<MessageTypeRaw>,
+
+	#[serde(rename = "uzenet")]
+	pub message: MessageContentRaw,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MessageContentRaw {
+	#[serde(rename = "azonosito")]
+	pub id: i64,
+	#[serde(rename = "kuldesDatum")]
+	pub date_sent: String,
+	#[serde(rename = "feladoNev")]
+	pub senders_name: String,
+	#[serde(rename = "feladoTitulus")]
+	pub senders_title: Option<String>,
+
+	#[serde(rename = "targy")]
+	pub subject: String,
+	#[serde(rename = "szoveg")]
+	/// html, see [MessageRaw::text_extract]
+	pub text: String,
+
+	#[serde(rename = "cimzettLista")]
+	#[serde(default)]
+	pub recipients: Vec<RecipientRaw>,
+	#[serde(rename = "csatolmanyok")]
+	#[serde(default)]
+	pub attachments: Vec<AttachmentRaw>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecipientRaw {
+	#[serde(rename = "azonosito")]
+	pub id: Option<i64>,
+	#[serde(rename = "kretaAzonosito")]
+	pub kreta_id: Option<i64>,
+	#[serde(rename = "nev")]
+	pub name: String,
+	#[serde(rename = "tipus")]
+	/// student, parent, class, etc
+	pub typ: Option<MessageTypeRaw>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AttachmentRaw {
+	#[serde(rename = "azonosito")]
+	pub id: i64,
+	#[serde(rename = "fajlNev")]
+	pub file_name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// the eugyintezes version of [UidNameAndDescRaw](crate::client::timetable::UidNameAndDescRaw)
+pub struct MessageTypeRaw {
+	#[serde(rename = "azonosito")]
+	pub id: i64,
+	#[serde(rename = "kod")]
+	pub code: String,
+	#[serde(rename = "rovidNev")]
+	pub short_name: Option<String>,
+	#[serde(rename = "nev")]
+	pub name: String,
+	#[serde(rename = "leiras")]
+	pub desc: Option<String>,
+}
+
+impl MessageRaw {
+	/// same as [HomeworkRaw::text_extract](crate::client::homework::HomeworkRaw::text_extract) but for messages
+	pub fn text_extract(&self) -> String {
+		super::html_to_text(&self.message.text)
+	}
+}
@@ -5,7 +5,30 @@ pub use client::*;
 
 pub mod refresh;
 
+#[cfg(feature = "client")]
+#[cfg(feature = "timerange")]
+pub(crate) mod range;
+
+pub mod absences;
+pub mod calendar;
+pub mod class_groups;
+pub mod consultation_hours;
 pub mod exam;
+pub mod grades;
 pub mod homework;
+pub mod messages;
+pub mod notes;
+pub mod noticeboard;
+pub mod profile;
 pub mod timetable;
-pub mod absences;
+
+/// the text of an html fragment (homework, notes, messages, etc), without the tags \
+/// every text node ends up on its own line
+pub(crate) fn html_to_text(html: &str) -> String {
+	let fragment = scraper::Html::parse_fragment(html);
+	fragment
+		.root_element()
+		.text()
+		.collect::<Vec<_>>()
+		.join("\n")
+}
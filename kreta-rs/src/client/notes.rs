@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use super::Client;
+#[cfg(feature = "client")]
+use crate::error::{KretaError, from_json};
+
+#[cfg(feature = "client")]
+impl Client {
+	/// https://nzx.hu/kreta-api/mobileapi/getnotes \
+	/// returns every note (feljegyzes) of the current school year, no need to split it up
+	pub async fn notes(&self) -> Result<Vec<NoteRaw>, KretaError> {
+		let url = format!("{}/ellenorzo/v3/sajat/Feljegyzesek", self.api_url());
+		let resp = self.get(&url).await?;
+
+		let resp = resp.text().await?;
+		let resp: Vec<NoteRaw> = from_json(&url, &resp)?;
+
+		Ok(resp)
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// as returned by https://[instituteCode].e-kreta.hu/ellenorzo/v3/sajat/Feljegyzesek
+pub struct NoteRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+
+	#[serde(rename = "Cim")]
+	pub title: String,
+	#[serde(rename = "Tartalom")]
+	/// plain text version of the note
+	pub body: String,
+	#[serde(rename = "TartalomFormazott")]
+	/// html fragment version of the note, see [NoteRaw::text_extract]
+	pub body_html: Option<String>,
+
+	#[serde(rename = "KeszitoTanarNeve")]
+	pub teachers_name: String,
+	#[serde(rename = "Tipus")]
+	/// see [NoteRaw::kind]
+	pub typ: UidNameAndDescRaw,
+
+	#[serde(rename = "Datum")]
+	pub date: String,
+	#[serde(rename = "KeszitesDatuma")]
+	pub date_of_creation: String,
+	#[serde(rename = "LattamozasDatuma")]
+	/// the date the note was marked as read (lattamozva), None if it hasn't been yet
+	pub date_read: Option<String>,
+
+	#[serde(rename = "OsztalyCsoport")]
+	pub class_group: Option<ClassGroupRaw>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// derived from [NoteRaw::typ]
+pub enum NoteKind {
+	/// dicseret
+	Praise,
+	/// figyelmezteto
+	Warning,
+	/// intes, rovo
+	Reprimand,
+	/// uzenet, or anything else we haven't seen before
+	Other,
+}
+impl NoteKind {
+	pub fn from_name(name: &str) -> Self {
+		let name = name.to_lowercase();
+		if name.contains("dicseret") {
+			Self::Praise
+		} else if name.contains("figyelmezteto") {
+			Self::Warning
+		} else if name.contains("intes") || name.contains("rovo") {
+			Self::Reprimand
+		} else {
+			Self::Other
+		}
+	}
+}
+
+impl NoteRaw {
+	pub fn kind(&self) -> NoteKind {
+		NoteKind::from_name(&self.typ.name)
+	}
+	/// whether the note has been seen (lattamozva) through the official app
+	pub fn is_read(&self) -> bool {
+		self.date_read.is_some()
+	}
+
+	/// same as [HomeworkRaw::text_extract](crate::client::homework::HomeworkRaw::text_extract),
+	/// falls back to the plain text body if there's no html version
+	pub fn text_extract(&self) -> String {
+		match &self.body_html {
+			Some(html) => super::html_to_text(html),
+			None => self.body.clone(),
+		}
+	}
+}
+
+pub use crate::client::homework::ClassGroupRaw;
+pub use crate::client::timetable::UidNameAndDescRaw;
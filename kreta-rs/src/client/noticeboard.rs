@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use super::Client;
+#[cfg(feature = "client")]
+use crate::error::{KretaError, from_json};
+
+#[cfg(feature = "client")]
+impl Client {
+	/// https://nzx.hu/kreta-api/mobileapi/getnoticeboarditems \
+	/// announcements posted on the school's noticeboard (faliujsag)
+	pub async fn noticeboard(&self) -> Result<Vec<NoticeboardItemRaw>, KretaError> {
+		let url = format!("{}/ellenorzo/v3/sajat/FaliujsagElemek", self.api_url());
+		let resp = self.get(&url).await?;
+
+		let resp = resp.text().await?;
+		let resp: Vec<NoticeboardItemRaw> = from_json(&url, &resp)?;
+
+		Ok(resp)
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// as returned by https://[instituteCode].e-kreta.hu/ellenorzo/v3/sajat/FaliujsagElemek
+pub struct NoticeboardItemRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+
+	#[serde(rename = "Cim")]
+	pub title: String,
+	#[serde(rename = "Tartalom")]
+	/// html, see [NoticeboardItemRaw::text_extract]
+	pub content: String,
+	#[serde(rename = "RogzitoNeve")]
+	pub posted_by: Option<String>,
+
+	#[serde(rename = "ErvenyessegKezdete")]
+	/// the announcement is shown from this date
+	pub valid_from: String,
+	#[serde(rename = "ErvenyessegVege")]
+	/// until this date
+	pub valid_until: String,
+}
+
+impl NoticeboardItemRaw {
+	/// same as [HomeworkRaw::text_extract](crate::client::homework::HomeworkRaw::text_extract) but for noticeboard items
+	pub fn text_extract(&self) -> String {
+		super::html_to_text(&self.content)
+	}
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use super::Client;
+#[cfg(feature = "client")]
+use crate::error::{KretaError, from_json};
+
+#[cfg(feature = "client")]
+impl Client {
+	/// https://nzx.hu/kreta-api/mobileapi/getstudent \
+	/// everything kreta knows about the logged in student
+	pub async fn student_profile(&self) -> Result<StudentProfileRaw, KretaError> {
+		let url = format!("{}/ellenorzo/v3/sajat/TanuloAdatlap", self.api_url());
+		let resp = self.get(&url).await?;
+
+		let resp = resp.text().await?;
+		let resp: StudentProfileRaw = from_json(&url, &resp)?;
+
+		Ok(resp)
+	}
+
+	/// the settings and enabled modules of the logged in student's school
+	pub async fn institution(&self) -> Result<InstitutionRaw, KretaError> {
+		let url = format!("{}/ellenorzo/v3/sajat/Intezmenyek", self.api_url());
+		let resp = self.get(&url).await?;
+
+		let resp = resp.text().await?;
+		let resp: InstitutionRaw = from_json(&url, &resp)?;
+
+		Ok(resp)
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// as returned by https://[instituteCode].e-kreta.hu/ellenorzo/v3/sajat/TanuloAdatlap
+pub struct StudentProfileRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+
+	#[serde(rename = "Nev")]
+	pub name: String,
+	#[serde(rename = "SzuletesiNev")]
+	pub birth_name: Option<String>,
+	#[serde(rename = "AnyjaNeve")]
+	pub mothers_name: Option<String>,
+
+	#[serde(rename = "SzuletesiDatum")]
+	pub birth_date: Option<String>,
+	#[serde(rename = "SzuletesiHely")]
+	pub birth_place: Option<String>,
+
+	#[serde(rename = "EmailCim")]
+	pub email: Option<String>,
+	#[serde(rename = "Telefonszam")]
+	pub phone_number: Option<String>,
+	#[serde(rename = "Cimek")]
+	#[serde(default)]
+	pub addresses: Vec<String>,
+
+	#[serde(rename = "Gondviselok")]
+	#[serde(default)]
+	pub guardians: Vec<GuardianRaw>,
+	#[serde(rename = "Bankszamla")]
+	/// used for paying school fees
+	pub bank_account: Option<BankAccountRaw>,
+
+	#[serde(rename = "IntezmenyAzonosito")]
+	/// same as the institute code used for logging in
+	pub inst_id: String,
+	#[serde(rename = "IntezmenyNev")]
+	pub inst_name: String,
+	#[serde(rename = "Intezmeny")]
+	pub institution: Option<InstitutionRaw>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GuardianRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+	#[serde(rename = "Nev")]
+	pub name: String,
+	#[serde(rename = "EmailCim")]
+	pub email: Option<String>,
+	#[serde(rename = "Telefonszam")]
+	pub phone_number: Option<String>,
+	#[serde(rename = "IsTorvenyesKepviselo")]
+	/// whether the guardian is also the student's legal representative
+	pub is_legal_representative: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BankAccountRaw {
+	#[serde(rename = "BankszamlaSzam")]
+	pub account_number: Option<String>,
+	#[serde(rename = "BankszamlaTulajdonosNeve")]
+	pub owners_name: Option<String>,
+	#[serde(rename = "BankszamlaTulajdonosTipusId")]
+	pub owner_type_id: Option<i32>,
+	#[serde(rename = "IsReadOnly")]
+	pub is_read_only: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// as returned by https://[instituteCode].e-kreta.hu/ellenorzo/v3/sajat/Intezmenyek
+pub struct InstitutionRaw {
+	#[serde(rename = "Uid")]
+	pub uid: String,
+	#[serde(rename = "RovidNev")]
+	/// short name of the school, usually the same as the institute code
+	pub short_name: String,
+	#[serde(rename = "Rendszermodulok")]
+	#[serde(default)]
+	pub modules: Vec<ModuleRaw>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// a feature of kreta that may or may not be enabled in a given school
+pub struct ModuleRaw {
+	#[serde(rename = "Tipus")]
+	pub typ: String,
+	#[serde(rename = "IsAktiv")]
+	pub is_active: bool,
+	#[serde(rename = "Url")]
+	pub url: Option<String>,
+}
@@ -0,0 +1,41 @@
+//! shared by the *_range functions, which all take a half-open range of days: `from..to` returns everything
+//! from `from` up to but not including `to`, no matter how the endpoint itself treats datumIg
+
+use std::{collections::HashSet, ops::Range};
+
+use chrono::NaiveDate;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// what an endpoint does with the day in datumIg, see login_test's fucking_query_limits
+pub(crate) enum RangeEnd {
+	/// it's returned too (HaziFeladatok, BejelentettSzamonkeresek, Mulasztasok, Ertekelesek, Fogadoorak)
+	Inclusive,
+	/// it's left out (OrarendElemek)
+	Exclusive,
+}
+
+/// splits `range` into chunks of at most `max_days` days, formatted as (datumTol, datumIg) \
+/// the chunks neither overlap nor leave a day out
+pub(crate) fn query_chunks(
+	range: Range<NaiveDate>,
+	max_days: u64,
+	end: RangeEnd,
+) -> impl Iterator<Item = (String, String)> {
+	timerange::date_range(range, max_days).map(move |chunk| {
+		let to = match end {
+			RangeEnd::Exclusive => chunk.end,
+			// chunks are never empty, so this is still >= chunk.start
+			RangeEnd::Inclusive => chunk.end.pred_opt().unwrap_or(chunk.end),
+		};
+		(
+			chunk.start.format("%Y-%m-%d").to_string(),
+			to.format("%Y-%m-%d").to_string(),
+		)
+	})
+}
+
+/// keeps the first of everything with the same uid, in case kreta returns something in two chunks anyway
+pub(crate) fn dedup_by_uid<T>(items: &mut Vec<T>, uid: impl Fn(&T) -> &str) {
+	let mut seen = HashSet::new();
+	items.retain(|item| seen.insert(uid(item).to_string()));
+}
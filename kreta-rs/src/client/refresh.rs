@@ -3,7 +3,10 @@ use std::borrow::Cow;
 use serde::Serialize;
 
 #[cfg(feature = "client")]
-use crate::login::TokensRaw;
+use crate::{
+	error::{KretaError, from_json},
+	login::TokensRaw,
+};
 
 #[derive(Clone, Debug, Serialize)]
 pub struct RefreshTokenBody<'a> {
@@ -17,69 +20,73 @@ pub struct RefreshTokenBody<'a> {
 impl crate::client::Client {
 	/// uses the refresh token to get a new access token but doesn't rewrite itself to use the new access
 	/// token so this probably isn't something you should use
-	async fn refresh_new(&self) -> anyhow::Result<TokensRaw> {
-		use anyhow::Context;
-
-		use crate::login::login_flow::CLIENT_ID;
+	async fn refresh_new(&self, refresh_token: &str) -> Result<TokensRaw, KretaError> {
+		use crate::login::login_flow::{CLIENT_ID, token_error_for_status};
 
 		let grant_type = "refresh_token";
 		let body = RefreshTokenBody {
 			institute_code: (&self.inst_id).into(),
-			refresh_token: (&self.tokens.refresh_token).into(),
+			refresh_token: refresh_token.into(),
 			grant_type: grant_type.into(),
 			client_id: CLIENT_ID.into(),
 		};
 
+		let token_url = self.config.token_url();
 		let req = self
 			.client
-			.post("https://idp.e-kreta.hu/connect/token")
-			.header(
-				"User-Agent",
-				"hu.ekreta.student/5.8.0+2025082301/SM-S9280/9/28",
-			)
+			.post(&token_url)
+			.header("User-Agent", &self.config.token_user_agent)
 			.form(&body)
 			.build()?;
-		let resp = self.client.execute(req).await?;
+		let resp = self.config.execute(&self.client, req).await?;
+		let resp = token_error_for_status(&token_url, resp).await?;
 
-		let status_code = resp.status();
-		if !status_code.is_success() {
-			use anyhow::anyhow;
+		let resp = resp.text().await?;
+		let tokens: TokensRaw = from_json(&token_url, &resp)?;
+		Ok(tokens)
+	}
 
-			let body = resp.text().await?;
-			let err = anyhow!(
-				"using the refresh token to get a new access token, received non-ok status code: {status_code}\n{body}"
-			);
-			return Err(err);
-		}
+	/// only call with refresh_lock held
+	async fn refresh_locked(&self) -> Result<(), KretaError> {
+		let refresh_token = self.token_state().tokens.refresh_token;
+		let tokens = self.refresh_new(&refresh_token).await?;
+		self.set_tokens(tokens);
 
-		let tokens: TokensRaw = resp.json().await.with_context(|| {
-			format!(
-				"everything went fine but the body of the /connect/token endpoint isn't valid json"
-			)
-		})?;
-		Ok(tokens)
+		Ok(())
 	}
 
-	pub async fn refresh(&mut self) -> anyhow::Result<()> {
-		use anyhow::Context;
+	/// gets a new access token, even if the current one is still valid
+	pub async fn refresh(&self) -> Result<(), KretaError> {
+		let _guard = self.refresh_lock.lock().await;
+		self.refresh_locked().await
+	}
+
+	/// whether the access token has expired or is about to
+	pub fn is_access_expired(&self) -> bool {
 		use std::time::{Duration, Instant};
 
-		let tokens = self
-			.refresh_new()
-			.await
-			.with_context(|| "while getting new access token (Client.refresh())")?;
+		self.token_state().access_expires <= Instant::now() + Duration::from_secs(30)
+	}
 
-		self.access_expires = Instant::now() + Duration::from_secs(tokens.expires_in.abs() as _);
-		self.tokens = tokens;
+	/// every request calls this before being sent, so you don't have to
+	pub async fn refresh_if_needed(&self) -> Result<(), KretaError> {
+		if !self.is_access_expired() {
+			return Ok(());
+		}
 
+		let _guard = self.refresh_lock.lock().await;
+		// another request might have refreshed while we were waiting for the lock
+		if self.is_access_expired() {
+			self.refresh_locked().await?;
+		}
 		Ok(())
 	}
 
-	pub async fn refresh_if_needed(&mut self) -> anyhow::Result<()> {
-		use std::time::Instant;
-
-		if self.access_expires <= Instant::now() {
-			self.refresh().await?;
+	/// refreshes after kreta rejected `rejected_token`, unless another request already did
+	pub(crate) async fn refresh_rejected(&self, rejected_token: &str) -> Result<(), KretaError> {
+		let _guard = self.refresh_lock.lock().await;
+		if self.access_token() == rejected_token {
+			self.refresh_locked().await?;
 		}
 		Ok(())
 	}
@@ -0,0 +1,152 @@
+//! where kreta-rs sends its requests and how. the defaults talk to the real kreta,
+//! but everything can be pointed at a local mock for testing
+
+#[cfg(feature = "cassette")]
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{
+	error::KretaError,
+	limit::{Backoff, RateLimit},
+};
+
+#[derive(Clone, Debug)]
+pub struct ClientConfig {
+	/// the oauth server that handles logging in, without a trailing slash
+	pub idp_url: String,
+	/// the ellenorzo api, `{inst_id}` is replaced with the institute code
+	pub api_url: String,
+	/// the e-ugyintezes api, used for messages
+	pub eugyintezes_url: String,
+	/// where the idp redirects after logging in, we only ever read the code from it
+	pub redirect_uri: String,
+
+	/// used for the login page and the api, kreta doesn't like requests without a browser-ish user agent
+	pub user_agent: String,
+	/// used for /connect/token, the official app's user agent
+	pub token_user_agent: String,
+
+	/// timeout of entire requests, None for no timeout
+	pub timeout: Option<Duration>,
+	pub connect_timeout: Option<Duration>,
+	/// proxy every request through this url (http, https or socks5)
+	pub proxy: Option<String>,
+	/// refuse to send anything over plain http. turn this off to use a local mock
+	pub https_only: bool,
+
+	/// at most this many requests of a [Client](crate::client::Client) are sent at once, the rest wait. None for no limit
+	pub max_concurrent_requests: Option<usize>,
+	/// every request of a [Client](crate::client::Client) has to fit into this, None for no limit
+	pub rate_limit: Option<RateLimit>,
+	/// retrying requests kreta answered with 429 or 5xx
+	pub backoff: Backoff,
+
+	#[cfg(feature = "cassette")]
+	/// if set, every request is recorded into or replayed from this cassette
+	pub cassette: Option<Arc<crate::Cassette>>,
+}
+
+impl Default for ClientConfig {
+	fn default() -> Self {
+		Self {
+			idp_url: "https://idp.e-kreta.hu".into(),
+			api_url: "https://{inst_id}.e-kreta.hu".into(),
+			eugyintezes_url: "https://eugyintezes.e-kreta.hu".into(),
+			redirect_uri: "https://mobil.e-kreta.hu/ellenorzo-student/prod/oauthredirect".into(),
+
+			user_agent: "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36".into(),
+			token_user_agent: "hu.ekreta.student/5.8.0+2025082301/SM-S9280/9/28".into(),
+
+			timeout: Some(Duration::from_secs(60)),
+			connect_timeout: Some(Duration::from_secs(10)),
+			proxy: None,
+			https_only: true,
+
+			max_concurrent_requests: Some(4),
+			rate_limit: Some(RateLimit::per_second(10)),
+			backoff: Backoff::default(),
+
+			#[cfg(feature = "cassette")]
+			cassette: None,
+		}
+	}
+}
+
+impl ClientConfig {
+	/// the default config, except every url points to `base_url` and plain http is allowed \
+	/// `{inst_id}` is kept as a path segment, so a mock can still tell the schools apart
+	pub fn local(base_url: &str) -> Self {
+		let base_url = base_url.trim_end_matches('/');
+		Self {
+			idp_url: base_url.into(),
+			api_url: format!("{base_url}/{{inst_id}}"),
+			eugyintezes_url: base_url.into(),
+			redirect_uri: format!("{base_url}/oauthredirect"),
+			https_only: false,
+			..Default::default()
+		}
+	}
+
+	/// [ClientConfig::api_url] for the given institute
+	pub fn api_url(&self, inst_id: &str) -> String {
+		self.api_url.replace("{inst_id}", inst_id)
+	}
+
+	pub fn login_url(&self) -> String {
+		format!("{}/account/login", self.idp_url)
+	}
+	pub fn token_url(&self) -> String {
+		format!("{}/connect/token", self.idp_url)
+	}
+
+	/// the reqwest client every request of [LoginFlow](crate::login::LoginFlow) and [Client](crate::client::Client) is sent with
+	pub fn http_client(&self) -> Result<reqwest::Client, KretaError> {
+		let mut builder = reqwest::Client::builder()
+			.redirect(reqwest::redirect::Policy::limited(5))
+			.https_only(self.https_only)
+			.cookie_store(true)
+			.user_agent(&self.user_agent);
+
+		if let Some(timeout) = self.timeout {
+			builder = builder.timeout(timeout);
+		}
+		if let Some(connect_timeout) = self.connect_timeout {
+			builder = builder.connect_timeout(connect_timeout);
+		}
+		if let Some(proxy) = &self.proxy {
+			builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+		}
+
+		Ok(builder.build()?)
+	}
+
+	#[cfg(feature = "cassette")]
+	pub fn with_cassette(mut self, cassette: crate::Cassette) -> Self {
+		self.cassette = Some(Arc::new(cassette));
+		self
+	}
+
+	/// tells the cassette (if there's one) to scrub this from what it saves, like the institute code in the api urls
+	pub(crate) fn redact(&self, literal: &str) {
+		#[cfg(feature = "cassette")]
+		if let Some(cassette) = &self.cassette {
+			cassette.redact(literal);
+		}
+		#[cfg(not(feature = "cassette"))]
+		let _ = literal;
+	}
+
+	/// sends the request, or hands it to the cassette if there's one
+	pub(crate) async fn execute(
+		&self,
+		client: &reqwest::Client,
+		req: reqwest::Request,
+	) -> Result<reqwest::Response, KretaError> {
+		#[cfg(feature = "cassette")]
+		if let Some(cassette) = &self.cassette {
+			return cassette.execute(client, req).await;
+		}
+
+		Ok(client.execute(req).await?)
+	}
+}
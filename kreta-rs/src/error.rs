@@ -0,0 +1,171 @@
+//! the error type returned by every request kreta-rs sends, so callers can tell a wrong password apart from kreta being down
+
+use std::{fmt::Display, time::Duration};
+
+#[derive(Debug)]
+pub enum KretaError {
+	/// kreta didn't accept our access token (401). it's either expired or has been revoked,
+	/// refreshing or logging in again should fix it
+	Unauthorized { url: String },
+	/// the idp rejected the username, password or institute code
+	InvalidCredentials,
+	/// too many requests (429), try again after `retry_after` if kreta told us how long to wait
+	RateLimited {
+		url: String,
+		retry_after: Option<Duration>,
+	},
+	/// kreta is down for maintenance (karbantartas)
+	Maintenance { url: String },
+	/// any other non-success status code
+	Http {
+		url: String,
+		status: u16,
+		body: String,
+	},
+	/// kreta returned something we can't deserialize, which usually means the schema changed \
+	/// path is the json path of the field that failed
+	Deserialize {
+		url: String,
+		path: String,
+		message: String,
+		body: String,
+	},
+	/// the login page doesn't look like it used to, so [LoginFlow](crate::login::LoginFlow) can't log in anymore
+	LoginPageChanged(String),
+	#[cfg(feature = "client")]
+	/// the request couldn't be sent or the response couldn't be read (network errors, timeouts, etc)
+	Request(reqwest::Error),
+	#[cfg(feature = "cassette")]
+	/// the cassette couldn't be read or written, or it has no response recorded for a request
+	Cassette(String),
+}
+
+impl Display for KretaError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Unauthorized { url } => write!(f, "{url} returned 401 unauthorized"),
+			Self::InvalidCredentials => write!(f, "invalid username, password or institute code"),
+			Self::RateLimited { url, retry_after } => match retry_after {
+				Some(retry_after) => write!(
+					f,
+					"{url} rate limited us, retry after {}s",
+					retry_after.as_secs()
+				),
+				None => write!(f, "{url} rate limited us"),
+			},
+			Self::Maintenance { url } => write!(f, "{url} is down for maintenance"),
+			Self::Http { url, status, body } => write!(f, "{url} returned {status}\n{body}"),
+			Self::Deserialize {
+				url,
+				path,
+				message,
+				body,
+			} => write!(
+				f,
+				"failed to deserialize response from {url} at {path}: {message}\n{body}"
+			),
+			Self::LoginPageChanged(msg) => write!(f, "the kreta login page changed: {msg}"),
+			#[cfg(feature = "client")]
+			Self::Request(err) => write!(f, "{err}"),
+			#[cfg(feature = "cassette")]
+			Self::Cassette(msg) => write!(f, "cassette: {msg}"),
+		}
+	}
+}
+
+impl std::error::Error for KretaError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			#[cfg(feature = "client")]
+			Self::Request(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+#[cfg(feature = "client")]
+impl From<reqwest::Error> for KretaError {
+	fn from(err: reqwest::Error) -> Self {
+		Self::Request(err)
+	}
+}
+
+impl KretaError {
+	/// finds the KretaError somewhere in the chain of an anyhow error, if there's one \
+	/// useful for the *_range functions and [Client::full_login](crate::client::Client::full_login), which add context to the errors they return
+	pub fn find(err: &anyhow::Error) -> Option<&KretaError> {
+		err.chain().find_map(|err| err.downcast_ref::<KretaError>())
+	}
+
+	/// whether logging in again (or refreshing) could fix this error
+	pub fn is_auth(&self) -> bool {
+		matches!(self, Self::Unauthorized { .. } | Self::InvalidCredentials)
+	}
+
+	/// whether the same request could succeed if sent again later
+	pub fn is_temporary(&self) -> bool {
+		match self {
+			Self::RateLimited { .. } | Self::Maintenance { .. } => true,
+			Self::Http { status, .. } => *status >= 500,
+			#[cfg(feature = "client")]
+			Self::Request(err) => err.is_timeout() || err.is_connect(),
+			_ => false,
+		}
+	}
+}
+
+#[cfg(feature = "client")]
+/// turns a non-success response into the matching KretaError
+pub(crate) async fn error_for_status(
+	url: &str,
+	resp: reqwest::Response,
+) -> Result<reqwest::Response, KretaError> {
+	let status = resp.status();
+	if status.is_success() {
+		return Ok(resp);
+	}
+
+	let url = url.to_string();
+	let retry_after = retry_after(&resp);
+	let body = resp.text().await?;
+
+	let err = match status.as_u16() {
+		401 => KretaError::Unauthorized { url },
+		429 => KretaError::RateLimited { url, retry_after },
+		503 => KretaError::Maintenance { url },
+		_ if is_maintenance_page(&body) => KretaError::Maintenance { url },
+		status => KretaError::Http { url, status, body },
+	};
+	Err(err)
+}
+
+#[cfg(feature = "client")]
+/// the Retry-After header in seconds, kreta doesn't seem to ever send it as a date
+pub(crate) fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+	resp.headers()
+		.get(reqwest::header::RETRY_AFTER)
+		.and_then(|val| val.to_str().ok())
+		.and_then(|val| val.trim().parse().ok())
+		.map(Duration::from_secs)
+}
+
+#[cfg(feature = "client")]
+/// kreta sometimes serves its maintenance page with random status codes
+fn is_maintenance_page(body: &str) -> bool {
+	let body = body.to_lowercase();
+	body.contains("karbantartás") || body.contains("karbantartas")
+}
+
+#[cfg(feature = "client")]
+/// [serde_json::from_str] but the error is a [KretaError::Deserialize]
+pub(crate) fn from_json<T: serde::de::DeserializeOwned>(
+	url: &str,
+	body: &str,
+) -> Result<T, KretaError> {
+	serde_json::from_str(body).map_err(|err| KretaError::Deserialize {
+		url: url.into(),
+		path: err.path().to_string(),
+		message: err.inner().to_string(),
+		body: body.into(),
+	})
+}
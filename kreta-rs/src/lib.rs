@@ -1,2 +1,20 @@
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "cassette")]
+pub mod cassette;
+#[cfg(feature = "cassette")]
+pub use cassette::Cassette;
 pub mod client;
+#[cfg(feature = "client")]
+pub mod config;
+#[cfg(feature = "client")]
+pub use config::ClientConfig;
+pub mod error;
+pub use error::KretaError;
+#[cfg(feature = "client")]
+pub mod limit;
+#[cfg(feature = "client")]
+pub use limit::{Backoff, RateLimit};
 pub mod login;
+#[cfg(feature = "model")]
+pub mod model;
@@ -0,0 +1,160 @@
+//! keeps a [Client](crate::client::Client) from flooding kreta: a cap on how many of its requests can be in flight at once,
+//! a token bucket every request has to take a token from, and backing off when kreta answers with 429 or 5xx \
+//! configured with [ClientConfig](crate::ClientConfig), every client has its own limits
+
+use std::{
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::ClientConfig;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// a token bucket that holds `requests` tokens and is refilled at `requests` per `per`,
+/// so bursts of up to `requests` go through immediately and anything after that is spread out
+pub struct RateLimit {
+	pub requests: u32,
+	pub per: Duration,
+}
+impl RateLimit {
+	pub fn per_second(requests: u32) -> Self {
+		Self {
+			requests,
+			per: Duration::from_secs(1),
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// how requests that got 429 or 5xx are retried. 5xx is only retried for idempotent requests (get, put, delete),
+/// so booking a consultation slot or sending a message won't happen twice
+pub struct Backoff {
+	/// 0 turns retrying off
+	pub max_retries: u32,
+	/// the first retry waits this long, every one after that twice as long as the one before
+	pub base_delay: Duration,
+	/// never wait longer than this between two tries. if kreta's Retry-After is longer, we give up instead
+	pub max_delay: Duration,
+}
+impl Default for Backoff {
+	fn default() -> Self {
+		Self {
+			max_retries: 3,
+			base_delay: Duration::from_secs(1),
+			max_delay: Duration::from_secs(30),
+		}
+	}
+}
+impl Backoff {
+	pub fn none() -> Self {
+		Self {
+			max_retries: 0,
+			..Default::default()
+		}
+	}
+
+	/// how long to wait before trying again, None if `resp` should be returned as is \
+	/// `attempt` is the number of retries so far
+	pub(crate) fn delay(
+		&self,
+		attempt: u32,
+		idempotent: bool,
+		resp: &reqwest::Response,
+	) -> Option<Duration> {
+		let status = resp.status();
+		let retryable = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+			|| (status.is_server_error() && idempotent);
+		if !retryable || attempt >= self.max_retries {
+			return None;
+		}
+
+		match crate::error::retry_after(resp) {
+			Some(retry_after) if retry_after > self.max_delay => None,
+			Some(retry_after) => Some(retry_after),
+			None => {
+				let delay = self.base_delay.saturating_mul(2u32.saturating_pow(attempt));
+				Some(delay.min(self.max_delay))
+			}
+		}
+	}
+}
+
+/// the limits of a single client, built from its config
+pub(crate) struct Limiter {
+	in_flight: Option<Semaphore>,
+	bucket: Option<Mutex<Bucket>>,
+}
+impl Limiter {
+	pub(crate) fn new(config: &ClientConfig) -> Self {
+		Self {
+			in_flight: config
+				.max_concurrent_requests
+				.map(|max| Semaphore::new(max.max(1))),
+			bucket: config.rate_limit.map(|rate| Mutex::new(Bucket::new(rate))),
+		}
+	}
+
+	/// waits until a request can be sent. hold on to what this returns until the response has arrived
+	pub(crate) async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+		let permit = match &self.in_flight {
+			Some(in_flight) => Some(
+				in_flight
+					.acquire()
+					.await
+					.expect("the semaphore is never closed"),
+			),
+			None => None,
+		};
+
+		if let Some(bucket) = &self.bucket {
+			loop {
+				let wait = bucket.lock().unwrap_or_else(|err| err.into_inner()).take();
+				match wait {
+					Some(wait) => tokio::time::sleep(wait).await,
+					None => break,
+				}
+			}
+		}
+
+		permit
+	}
+}
+
+struct Bucket {
+	rate: RateLimit,
+	tokens: f64,
+	refilled_at: Instant,
+}
+impl Bucket {
+	fn new(rate: RateLimit) -> Self {
+		Self {
+			rate,
+			tokens: rate.requests as f64,
+			refilled_at: Instant::now(),
+		}
+	}
+
+	/// takes a token if there's one, otherwise returns how long until there will be
+	fn take(&mut self) -> Option<Duration> {
+		let capacity = self.rate.requests.max(1) as f64;
+		let per_token = self.rate.per.as_secs_f64() / capacity;
+
+		let now = Instant::now();
+		let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+		self.refilled_at = now;
+		if per_token > 0.0 {
+			self.tokens = (self.tokens + elapsed / per_token).min(capacity);
+		} else {
+			self.tokens = capacity;
+		}
+
+		if self.tokens >= 1.0 {
+			self.tokens -= 1.0;
+			None
+		} else {
+			Some(Duration::from_secs_f64((1.0 - self.tokens) * per_token))
+		}
+	}
+}
@@ -1,6 +1,5 @@
 use std::borrow::Cow;
 
-use anyhow::{Context, anyhow};
 use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
 use scraper::{Html, Selector};
 use serde::Serialize;
@@ -9,6 +8,10 @@ use sha2::Digest;
 pub const CLIENT_ID: &str = "kreta-ellenorzo-student-mobile-android";
 
 use super::{Credentials, TokensRaw};
+use crate::{
+	config::ClientConfig,
+	error::{KretaError, error_for_status, from_json},
+};
 
 /// a login flow implementation that does not require the user to open kreta's website to log in;
 /// we kinda cheat our way around it by parsing the login page and filing the post request manually, as if it was sent from the browser
@@ -17,19 +20,20 @@ use super::{Credentials, TokensRaw};
 /// (i'll probably look into doing oauth the correct way but it's still a question whether kreta will accept any arbitrary redirect_uri or only the ones it likes)
 pub struct LoginFlow {
 	client: reqwest::Client,
+	config: ClientConfig,
 }
 impl LoginFlow {
-	pub fn new() -> anyhow::Result<Self> {
-		let client = reqwest::Client::builder()
-			.redirect(reqwest::redirect::Policy::limited(5))
-			.https_only(true)
-			.cookie_store(true)
-			.user_agent("Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36")
-			.build()?;
-
-		Ok(Self { client })
+	pub fn new() -> Result<Self, KretaError> {
+		Self::with_config(ClientConfig::default())
+	}
+	pub fn with_config(config: ClientConfig) -> Result<Self, KretaError> {
+		let client = config.http_client()?;
+		Ok(Self { client, config })
 	}
 
+	pub fn config(&self) -> &ClientConfig {
+		&self.config
+	}
 	pub fn take_client(self) -> reqwest::Client {
 		self.client
 	}
@@ -68,15 +72,20 @@ fn challenge() -> (String, String) {
 }
 
 impl LoginFlow {
-	async fn begin_send(&self) -> anyhow::Result<(String, String)> {
+	async fn begin_send(&self) -> Result<(String, String), KretaError> {
 		let state = random_base64();
 		let nonce = random_base64();
 
 		let (verifier, challenge) = challenge();
 
-		let req = self.client.get(format!("https://idp.e-kreta.hu/connect/authorize?redirect_uri=https://mobil.e-kreta.hu/ellenorzo-student/prod/oauthredirect&client_id={CLIENT_ID}&response_type=code&prompt=login&state={state}&nonce={nonce}&scope=openid email offline_access kreta-ellenorzo-webapi.public kreta-eugyintezes-webapi.public kreta-fileservice-webapi.public kreta-mobile-global-webapi.public kreta-dkt-webapi.public kreta-ier-webapi.public&code_challenge={challenge}&code_challenge_method=S256")).build()?;
-		let resp = self.client.execute(req).await?;
-		let resp = resp.error_for_status()?;
+		let idp_url = &self.config.idp_url;
+		let redirect_uri = &self.config.redirect_uri;
+		let url = format!(
+			"{idp_url}/connect/authorize?redirect_uri={redirect_uri}&client_id={CLIENT_ID}&response_type=code&prompt=login&state={state}&nonce={nonce}&scope=openid email offline_access kreta-ellenorzo-webapi.public kreta-eugyintezes-webapi.public kreta-fileservice-webapi.public kreta-mobile-global-webapi.public kreta-dkt-webapi.public kreta-ier-webapi.public&code_challenge={challenge}&code_challenge_method=S256"
+		);
+		let req = self.client.get(&url).build()?;
+		let resp = self.config.execute(&self.client, req).await?;
+		let resp = error_for_status(&url, resp).await?;
 
 		let body = resp.text().await?;
 
@@ -84,34 +93,33 @@ impl LoginFlow {
 	}
 
 	/// requests the login screen and parses it, extracting whatever it is we need
-	pub async fn begin(&self) -> anyhow::Result<BeginData> {
-		let (verifier, body) = self
-			.begin_send()
-			.await
-			.with_context(|| format!("error while sending begin request"))?;
+	pub async fn begin(&self) -> Result<BeginData, KretaError> {
+		let (verifier, body) = self.begin_send().await?;
 
 		let document = Html::parse_document(&body);
 
-		let return_selector =
-			Selector::parse("input[id=\"ReturnUrl\"]").map_err(|a| anyhow!("{a}"))?;
+		let return_selector = Selector::parse("input[id=\"ReturnUrl\"]")
+			.map_err(|a| KretaError::LoginPageChanged(a.to_string()))?;
 		let return_url = document
 			.select(&return_selector)
 			.next()
 			.and_then(|a| a.value().attr("value"));
 		let return_url = return_url.ok_or_else(|| {
-			anyhow!("returned page doesn't have a ReturnUrl element ({return_selector:?})")
+			KretaError::LoginPageChanged(format!(
+				"returned page doesn't have a ReturnUrl element ({return_selector:?})"
+			))
 		})?;
 
 		let token_selector = Selector::parse("input[name=\"__RequestVerificationToken\"]")
-			.map_err(|a| anyhow!("{a}"))?;
+			.map_err(|a| KretaError::LoginPageChanged(a.to_string()))?;
 		let verification_token = document
 			.select(&token_selector)
 			.next()
 			.and_then(|a| a.value().attr("value"));
 		let verification_token = verification_token.ok_or_else(|| {
-			anyhow!(
+			KretaError::LoginPageChanged(format!(
 				"returned page doesn't have a __RequestVerificationToken element ({token_selector:?})"
-			)
+			))
 		})?;
 
 		let data = BeginData {
@@ -211,7 +219,7 @@ impl LoginFlow {
 		&self,
 		begin_data: &BeginData,
 		credentials: &Credentials,
-	) -> anyhow::Result<()> {
+	) -> Result<(), KretaError> {
 		let login_body = LoginBody::new(
 			&begin_data.return_url,
 			&begin_data.verification_token,
@@ -222,22 +230,22 @@ impl LoginFlow {
 
 		self.post_credentials_map(&login_body).await
 	}
-	/// meant to be used with [LoginBody] but u do whatever u want lowkey
-	pub async fn post_credentials_map<M: Serialize>(&self, map: &M) -> anyhow::Result<()> {
+	/// meant to be used with [LoginBody] but u do whatever u want lowkey \
+	/// kreta answers a wrong password with the login page and a 200, so we look for its error message
+	pub async fn post_credentials_map<M: Serialize>(&self, map: &M) -> Result<(), KretaError> {
+		let login_url = self.config.login_url();
 		let req = self
 			.client
-			.post("https://idp.e-kreta.hu/account/login")
+			.post(&login_url)
 			// .post("https://adgadgadgadg.free.beeceptor.com/babab")
 			.form(map)
 			.build()?;
-		let resp = self.client.execute(req).await?;
-
-		let status_code = resp.status();
-		if !status_code.is_success() {
-			let body = resp.text().await?;
-			let err =
-				anyhow!("post_credentials received non-ok status code: {status_code}\n{body}");
-			return Err(err);
+		let resp = self.config.execute(&self.client, req).await?;
+		let resp = error_for_status(&login_url, resp).await?;
+
+		let body = resp.text().await?;
+		if is_invalid_credentials_page(&body) {
+			return Err(KretaError::InvalidCredentials);
 		}
 
 		Ok(())
@@ -279,28 +287,33 @@ impl<'a> ConnectTokenBody<'a> {
 
 impl LoginFlow {
 	/// returns the url the return url forwarded the client to (see /thanks.py if all this is confusing)
-	async fn resolve_return_url(&self, begin_data: &BeginData) -> anyhow::Result<String> {
-		let basic_return_url = format!("https://idp.e-kreta.hu{}", begin_data.return_url);
+	async fn resolve_return_url(&self, begin_data: &BeginData) -> Result<String, KretaError> {
+		let basic_return_url = format!("{}{}", self.config.idp_url, begin_data.return_url);
 
 		let req = self.client.get(&basic_return_url).build()?;
-		let resp = self.client.execute(req).await?;
-		let resp = resp.error_for_status()?;
+		let resp = self.config.execute(&self.client, req).await?;
+		let resp = error_for_status(&basic_return_url, resp).await?;
 
 		Ok(resp.url().as_str().into())
 	}
-	async fn resolve_return_url_code(&self, begin_data: &BeginData) -> anyhow::Result<String> {
+	/// if the login didn't go through (which almost always means the credentials were wrong),
+	/// kreta redirects us back to the login page instead of giving us a code
+	async fn resolve_return_url_code(&self, begin_data: &BeginData) -> Result<String, KretaError> {
 		let resolved_return_url = self.resolve_return_url(begin_data).await?;
 
-		let code = resolved_return_url.split("code=").nth(1).ok_or_else(|| anyhow!("url returned by LoginFlow.resolve_return_url is invalid, as there's no code= segment\n{resolved_return_url}"))?;
-		let code = code.split('&').next().ok_or_else(|| anyhow!("url returned by LoginFlow.resolve_return_url is invalid, as there's no & symbol after code=\n{resolved_return_url}"))?;
+		let code = resolved_return_url
+			.split("code=")
+			.nth(1)
+			.ok_or(KretaError::InvalidCredentials)?;
+		let code = code.split('&').next().ok_or_else(|| KretaError::LoginPageChanged(format!("url returned by LoginFlow.resolve_return_url is invalid, as there's no & symbol after code=\n{resolved_return_url}")))?;
 
 		Ok(code.into())
 	}
 
-	pub async fn request_token(&self, begin_data: &BeginData) -> anyhow::Result<TokensRaw> {
+	pub async fn request_token(&self, begin_data: &BeginData) -> Result<TokensRaw, KretaError> {
 		let code = self.resolve_return_url_code(begin_data).await?;
 		let grant_type = "authorization_code";
-		let redirect_uri = "https://mobil.e-kreta.hu/ellenorzo-student/prod/oauthredirect";
+		let redirect_uri = &self.config.redirect_uri;
 		let code_verifier = &begin_data.verifier;
 
 		let connect_token_body = ConnectTokenBody::new_explicit(
@@ -313,32 +326,49 @@ impl LoginFlow {
 
 		self.request_token_map(&connect_token_body).await
 	}
-	pub async fn request_token_map<S: Serialize>(&self, map: &S) -> anyhow::Result<TokensRaw> {
+	pub async fn request_token_map<S: Serialize>(&self, map: &S) -> Result<TokensRaw, KretaError> {
+		let token_url = self.config.token_url();
 		let req = self
 			.client
-			.post("https://idp.e-kreta.hu/connect/token")
-			.header(
-				"User-Agent",
-				"hu.ekreta.student/5.8.0+2025082301/SM-S9280/9/28",
-			)
+			.post(&token_url)
+			.header("User-Agent", &self.config.token_user_agent)
 			.form(map)
 			.build()?;
 
-		let resp = self.client.execute(req).await?;
-		let status_code = resp.status();
-		if !status_code.is_success() {
-			let body = resp.text().await?;
-			let err =
-				anyhow!("post_credentials received non-ok status code: {status_code}\n{body}");
-			return Err(err);
-		}
+		let resp = self.config.execute(&self.client, req).await?;
+		let resp = token_error_for_status(&token_url, resp).await?;
 
-		let tokens: TokensRaw = resp.json().await.with_context(|| {
-			format!(
-				"everything went fine but the body of the /connect/token endpoint isn't valid json"
-			)
-		})?;
+		let resp = resp.text().await?;
+		let tokens: TokensRaw = from_json(&token_url, &resp)?;
 
 		Ok(tokens)
 	}
 }
+
+/// the login page with a "wrong username or password" message on it
+fn is_invalid_credentials_page(body: &str) -> bool {
+	body.contains("validation-summary-errors") || body.contains("Hibás felhasználónév vagy jelszó")
+}
+
+/// [error_for_status] for https://idp.e-kreta.hu/connect/token \
+/// an expired refresh token or authorization code is a 400 with invalid_grant, not a 401
+pub(crate) async fn token_error_for_status(
+	token_url: &str,
+	resp: reqwest::Response,
+) -> Result<reqwest::Response, KretaError> {
+	if resp.status() != reqwest::StatusCode::BAD_REQUEST {
+		return error_for_status(token_url, resp).await;
+	}
+
+	let body = resp.text().await?;
+	if body.contains("invalid_grant") {
+		return Err(KretaError::Unauthorized {
+			url: token_url.into(),
+		});
+	}
+	Err(KretaError::Http {
+		url: token_url.into(),
+		status: 400,
+		body,
+	})
+}
@@ -8,3 +8,6 @@ pub use credentials::Credentials;
 
 pub mod tokens;
 pub use tokens::TokensRaw;
+
+pub mod session;
+pub use session::Session;
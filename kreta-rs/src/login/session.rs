@@ -0,0 +1,43 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::TokensRaw;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// everything needed to resume a logged in [Client](crate::client::Client) without logging in again,
+/// get one from [Client::session](crate::client::Client::session) and save it wherever you want \
+/// contains the refresh token, so treat it like a password
+pub struct Session {
+	pub inst_id: String,
+	pub tokens: TokensRaw,
+	/// when the access token expires, in seconds since the unix epoch
+	pub access_expires_at: u64,
+}
+
+impl Session {
+	pub fn new(inst_id: String, tokens: TokensRaw, access_expires_at: SystemTime) -> Self {
+		let access_expires_at = access_expires_at
+			.duration_since(UNIX_EPOCH)
+			.unwrap_or_default()
+			.as_secs();
+
+		Self {
+			inst_id,
+			tokens,
+			access_expires_at,
+		}
+	}
+
+	pub fn access_expires_at(&self) -> SystemTime {
+		UNIX_EPOCH + Duration::from_secs(self.access_expires_at)
+	}
+
+	/// how long the access token is still valid for, zero if it has already expired \
+	/// the refresh token usually lives a lot longer, so an expired session can still be resumed
+	pub fn access_valid_for(&self) -> Duration {
+		self.access_expires_at()
+			.duration_since(SystemTime::now())
+			.unwrap_or_default()
+	}
+}
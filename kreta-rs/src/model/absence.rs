@@ -0,0 +1,110 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use super::{code, parse_time};
+use crate::client::absences::AbsenceRaw;
+use crate::client::exam::{ClassGroupRaw, SubjectRaw, UidNameAndDescRaw};
+
+#[derive(Clone, Debug)]
+/// [AbsenceRaw] with its dates parsed and its type & excuse status turned into enums
+pub struct Absence {
+	pub uid: String,
+	/// midnight on the day of the absence
+	pub date: DateTime<Tz>,
+	pub date_of_creation: DateTime<Tz>,
+
+	pub kind: AbsenceKind,
+	/// only Some for [AbsenceKind::Late]
+	pub late_by_minutes: Option<i32>,
+
+	pub excuse_status: ExcuseStatus,
+	/// why it's excused, like "Orvosi igazolás" in its desc. should be Some if [ExcuseStatus::Excused]
+	pub excuse_type: Option<UidNameAndDescRaw>,
+
+	/// the lesson that was missed
+	pub lesson_start: DateTime<Tz>,
+	pub lesson_end: DateTime<Tz>,
+	/// for the given day (hanyadik ora aznap)
+	pub lesson_index: i32,
+
+	pub mode: UidNameAndDescRaw,
+	pub subject: SubjectRaw,
+	pub teachers_name: String,
+	pub class_group: ClassGroupRaw,
+}
+impl TryFrom<AbsenceRaw> for Absence {
+	type Error = anyhow::Error;
+
+	fn try_from(raw: AbsenceRaw) -> anyhow::Result<Self> {
+		Ok(Self {
+			date: parse_time("absence date", &raw.date)?,
+			date_of_creation: parse_time("absence creation time", &raw.date_of_creation)?,
+			lesson_start: parse_time("absence lesson start time", &raw.lesson.start_time)?,
+			lesson_end: parse_time("absence lesson end time", &raw.lesson.end_time)?,
+			kind: AbsenceKind::from_raw(&raw.typ),
+			excuse_status: ExcuseStatus::from_raw(&raw.excuse_status),
+
+			uid: raw.uid,
+			late_by_minutes: raw.late_by_minutes,
+			excuse_type: raw.excuse_type,
+			lesson_index: raw.lesson.oraszam,
+			mode: raw.mode,
+			subject: raw.subject,
+			teachers_name: raw.teachers_name,
+			class_group: raw.class_group,
+		})
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Tipus
+pub enum AbsenceKind {
+	/// hianyzas
+	Absence,
+	/// keses
+	Late,
+	/// anything else, the code as kreta sent it
+	Other(String),
+}
+impl AbsenceKind {
+	pub fn from_raw(raw: &UidNameAndDescRaw) -> Self {
+		match code(raw) {
+			"hianyzas" => Self::Absence,
+			"keses" => Self::Late,
+			other => Self::Other(other.into()),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// IgazolasAllapota
+pub enum ExcuseStatus {
+	/// Igazolt
+	Excused,
+	/// Igazolando, waiting for an excuse
+	ToBeExcused,
+	/// Igazolatlan
+	Unexcused,
+	/// anything else, as kreta sent it
+	Other(String),
+}
+impl ExcuseStatus {
+	pub fn from_raw(raw: &str) -> Self {
+		match raw {
+			"Igazolt" => Self::Excused,
+			"Igazolando" => Self::ToBeExcused,
+			"Igazolatlan" => Self::Unexcused,
+			other => Self::Other(other.into()),
+		}
+	}
+}
+impl std::fmt::Display for ExcuseStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Excused => write!(f, "Igazolt"),
+			Self::ToBeExcused => write!(f, "Igazolandó"),
+			Self::Unexcused => write!(f, "Igazolatlan"),
+			Self::Other(other) => write!(f, "{other}"),
+		}
+	}
+}
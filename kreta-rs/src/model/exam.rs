@@ -0,0 +1,77 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use super::{code, parse_time};
+use crate::client::exam::{ClassGroupRaw, ExamRaw, SubjectRaw, UidNameAndDescRaw};
+
+#[derive(Clone, Debug)]
+/// [ExamRaw] with its dates parsed and its method turned into an enum
+pub struct Exam {
+	pub uid: String,
+	/// midnight on the day of the exam
+	pub date: DateTime<Tz>,
+
+	pub date_announced: DateTime<Tz>,
+	pub teachers_name: String,
+
+	/// for the given day (hanyadik ora aznap)
+	pub lesson_index_in_timetable: i32,
+	pub subject: SubjectRaw,
+	pub subject_name: String,
+
+	pub topic: String,
+	pub method: ExamMethod,
+	/// the method as it should be shown to the user, like "Írásbeli röpdolgozat"
+	pub method_desc: String,
+
+	pub class_group: ClassGroupRaw,
+}
+impl TryFrom<ExamRaw> for Exam {
+	type Error = anyhow::Error;
+
+	fn try_from(raw: ExamRaw) -> anyhow::Result<Self> {
+		Ok(Self {
+			date: parse_time("exam date", &raw.date)?,
+			date_announced: parse_time("exam announcement date", &raw.date_announced)?,
+			method: ExamMethod::from_raw(&raw.method),
+
+			uid: raw.uid,
+			teachers_name: raw.teachers_name,
+			lesson_index_in_timetable: raw.lesson_index_in_timetable,
+			subject: raw.subject,
+			subject_name: raw.subject_name,
+			topic: raw.topic,
+			method_desc: raw.method.desc,
+			class_group: raw.class_group,
+		})
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Modja
+pub enum ExamMethod {
+	/// irasbeli_ropdolgozat
+	Quiz,
+	/// irasbeli_temazaro_dolgozat
+	Test,
+	/// any other irasbeli_*
+	Written,
+	/// szobeli_*
+	Oral,
+	/// gyakorlati_*
+	Practical,
+	/// anything else, the code as kreta sent it
+	Other(String),
+}
+impl ExamMethod {
+	pub fn from_raw(raw: &UidNameAndDescRaw) -> Self {
+		match code(raw) {
+			"irasbeli_ropdolgozat" => Self::Quiz,
+			"irasbeli_temazaro_dolgozat" => Self::Test,
+			other if other.starts_with("irasbeli") => Self::Written,
+			other if other.starts_with("szobeli") => Self::Oral,
+			other if other.starts_with("gyakorlati") => Self::Practical,
+			other => Self::Other(other.into()),
+		}
+	}
+}
@@ -0,0 +1,60 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use super::parse_time;
+use crate::client::homework::{ClassGroupRaw, HomeworkRaw, SubjectRaw};
+
+#[derive(Clone, Debug)]
+/// [HomeworkRaw] with its dates parsed
+pub struct Homework {
+	pub uid: String,
+	pub subject: SubjectRaw,
+	pub subject_name: String,
+	pub teachers_name: String,
+	/// an html fragment, see [Homework::text_extract]
+	pub text: String,
+
+	/// midnight on the day it was given
+	pub date_assigned: DateTime<Tz>,
+	/// midnight on the day it's due
+	pub date_deadline: DateTime<Tz>,
+	pub date_registered: DateTime<Tz>,
+
+	pub is_registered_by_teacher: bool,
+	pub is_student_homework_enabled: bool,
+	pub is_solved: bool,
+	pub is_submittable: bool,
+	pub is_attachment_enabled: bool,
+
+	pub class_group: ClassGroupRaw,
+}
+impl Homework {
+	#[cfg(feature = "client")]
+	/// see [HomeworkRaw::text_extract]
+	pub fn text_extract(&self) -> String {
+		crate::client::html_to_text(&self.text)
+	}
+}
+impl TryFrom<HomeworkRaw> for Homework {
+	type Error = anyhow::Error;
+
+	fn try_from(raw: HomeworkRaw) -> anyhow::Result<Self> {
+		Ok(Self {
+			date_assigned: parse_time("homework assignment date", &raw.date_assigned)?,
+			date_deadline: parse_time("homework deadline", &raw.date_deadline)?,
+			date_registered: parse_time("homework registration time", &raw.date_registered)?,
+
+			uid: raw.uid,
+			subject: raw.subject,
+			subject_name: raw.subject_name,
+			teachers_name: raw.teachers_name,
+			text: raw.text,
+			is_registered_by_teacher: raw.is_registered_by_teacher,
+			is_student_homework_enabled: raw.is_student_homework_enabled,
+			is_solved: raw.is_solved,
+			is_submittable: raw.is_submittable,
+			is_attachment_enabled: raw.is_attachment_enabled,
+			class_group: raw.class_group,
+		})
+	}
+}
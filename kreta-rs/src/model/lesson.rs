@@ -0,0 +1,127 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use super::{code, parse_time};
+use crate::client::timetable::{ClassGroupRaw, LessonRaw, SubjectRaw, UidNameAndDescRaw};
+
+#[derive(Clone, Debug)]
+/// [LessonRaw] with its times parsed and its status & the student's presence turned into enums
+pub struct Lesson {
+	pub uid: String,
+	/// midnight on the day of the lesson
+	pub date: DateTime<Tz>,
+	/// if start == end, it's an all day entry (so far only seems to be school holidays & such)
+	pub start: DateTime<Tz>,
+	pub end: DateTime<Tz>,
+	pub name: String,
+
+	/// for the given day (hanyadik ora aznap)
+	pub lesson_index: Option<i32>,
+	/// for the whole school year
+	pub lesson_index_yearly: Option<i32>,
+
+	pub class_group: Option<ClassGroupRaw>,
+	pub teachers_name: Option<String>,
+	pub subject: Option<SubjectRaw>,
+	pub topic: Option<String>,
+	pub room_name: Option<String>,
+
+	pub lesson_type: UidNameAndDescRaw,
+	/// None if kreta didn't send one
+	pub presence: Option<Presence>,
+	pub status: LessonStatus,
+
+	pub substitute_teacher_name: Option<String>,
+	pub homework_uid: Option<String>,
+	pub announced_exam_uid: Option<String>,
+
+	pub created_at: DateTime<Tz>,
+	pub last_modified: DateTime<Tz>,
+}
+impl Lesson {
+	pub fn is_all_day(&self) -> bool {
+		self.start == self.end
+	}
+	pub fn is_cancelled(&self) -> bool {
+		self.status == LessonStatus::Cancelled
+	}
+	pub fn is_substituted(&self) -> bool {
+		self.substitute_teacher_name.is_some()
+	}
+}
+impl TryFrom<LessonRaw> for Lesson {
+	type Error = anyhow::Error;
+
+	fn try_from(raw: LessonRaw) -> anyhow::Result<Self> {
+		Ok(Self {
+			date: parse_time("lesson date", &raw.date)?,
+			start: parse_time("lesson start time", &raw.start_time)?,
+			end: parse_time("lesson end time", &raw.end_time)?,
+			created_at: parse_time("lesson creation time", &raw.created_at)?,
+			last_modified: parse_time("lesson modification time", &raw.last_modified)?,
+
+			presence: raw.student_presence.as_ref().map(Presence::from_raw),
+			status: LessonStatus::from_raw(&raw.status),
+
+			uid: raw.uid,
+			name: raw.name,
+			lesson_index: raw.oraszam,
+			lesson_index_yearly: raw.ora_eves_sorszama,
+			class_group: raw.class_group,
+			teachers_name: raw.teachers_name,
+			subject: raw.subject,
+			topic: raw.topic,
+			room_name: raw.room_name,
+			lesson_type: raw.lesson_type,
+			substitute_teacher_name: raw.substitute_teacher_name,
+			homework_uid: raw.homework_uid,
+			announced_exam_uid: raw.announced_exam_uid,
+		})
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Allapot
+pub enum LessonStatus {
+	/// Naplozott, the lesson was held and the teacher logged it
+	Logged,
+	/// Elmaradt
+	Cancelled,
+	/// anything else, the code as kreta sent it
+	Other(String),
+}
+impl LessonStatus {
+	pub fn from_raw(raw: &UidNameAndDescRaw) -> Self {
+		match code(raw) {
+			"Naplozott" => Self::Logged,
+			"Elmaradt" => Self::Cancelled,
+			other => Self::Other(other.into()),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// TanuloJelenlet
+pub enum Presence {
+	/// Jelenlet
+	Present,
+	/// Na: nem definialt, the lesson hasn't happened yet
+	NotRecorded,
+	/// Keses
+	Late,
+	/// Hianyzas
+	Absent,
+	/// anything else, the code as kreta sent it
+	Other(String),
+}
+impl Presence {
+	pub fn from_raw(raw: &UidNameAndDescRaw) -> Self {
+		match code(raw) {
+			"Jelenlet" => Self::Present,
+			"Na" => Self::NotRecorded,
+			"Keses" => Self::Late,
+			"Hianyzas" => Self::Absent,
+			other => Self::Other(other.into()),
+		}
+	}
+}
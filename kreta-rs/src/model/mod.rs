@@ -0,0 +1,38 @@
+//! typed versions of the *Raw structs: times are parsed into [DateTime]s in budapest time
+//! and the magic strings kreta uses as enums are turned into actual enums \
+//! convert with `TryFrom`, e.g. `Lesson::try_from(lesson_raw)?`. values the enums don't know about
+//! end up in an `Other` variant instead of being an error, only times that don't parse are errors
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::client::timetable::UidNameAndDescRaw;
+
+mod absence;
+mod exam;
+mod homework;
+mod lesson;
+
+pub use absence::{Absence, AbsenceKind, ExcuseStatus};
+pub use exam::{Exam, ExamMethod};
+pub use homework::Homework;
+pub use lesson::{Lesson, LessonStatus, Presence};
+
+/// every time kreta returns is utc, but all of them are meant in this timezone
+pub const TIMEZONE: Tz = chrono_tz::Europe::Budapest;
+
+/// parses a time returned by kreta (like 2026-02-01T23:00:00Z) into [TIMEZONE] \
+/// `field` is only used in the error message
+pub(crate) fn parse_time(field: &str, time: &str) -> anyhow::Result<DateTime<Tz>> {
+	let parsed: DateTime<Utc> = time
+		.parse()
+		.with_context(|| format!("while parsing {field} {time} as a datetime"))?;
+	Ok(parsed.with_timezone(&TIMEZONE))
+}
+
+/// enum-like values look like `{"Uid": "1,Jelenlet", "Nev": "Jelenlet", ...}`,
+/// this returns the part after the comma (Jelenlet)
+pub(crate) fn code(raw: &UidNameAndDescRaw) -> &str {
+	raw.uid.rsplit(',').next().unwrap_or(&raw.uid)
+}
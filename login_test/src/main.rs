@@ -3,7 +3,10 @@
 use anyhow::Context;
 use chrono::{TimeZone, Utc};
 use chrono_tz::Europe::Budapest;
-use kreta_rs::{client::Client, login::LoginFlow};
+use kreta_rs::{
+	client::Client,
+	login::{LoginFlow, Session},
+};
 
 mod creds_from_file;
 
@@ -135,7 +138,9 @@ async fn absences() -> anyhow::Result<()> {
 	let (from, to) = ("2025-12-13", "2026-02-03");
 	let (from, to) = (parse_simple_date(from)?, parse_simple_date(to)?);
 
-	let absences = client.absences_range(from, to).await?;
+	let absences = client
+		.absences_range(from.date_naive()..to.date_naive())
+		.await?;
 	println!("{absences:#?}");
 
 	Ok(())
@@ -157,7 +162,7 @@ async fn homework() -> anyhow::Result<()> {
 async fn start() -> anyhow::Result<()> {
 	let credentials = creds_from_file::read_from_file("./credentials.txt").await?;
 
-	let mut client = Client::full_login(&credentials).await?;
+	let client = Client::full_login(&credentials).await?;
 	tokio::time::sleep(std::time::Duration::from_secs(5)).await;
 
 	client.refresh().await?;
@@ -189,10 +194,38 @@ async fn gen_timetable() -> anyhow::Result<()> {
 	};
 
 	let timetable = client.timetable("2025-10-21", "2025-11-04").await?;
-	let calendar = timetable_to_ical::lessons_to_calendar_file(&timetable, &opts);
+	let calendar = timetable_to_ical::lessons_to_calendar_file(&timetable, &opts, None);
 
 	tokio::fs::write("./timetable.ical", &calendar).await?;
 
 	println!("all done");
 	Ok(())
 }
+
+/// resumes the session saved by the previous run instead of logging in every time
+async fn session() -> anyhow::Result<()> {
+	let path = "./session.json";
+
+	let client = match tokio::fs::read_to_string(path).await {
+		Ok(saved) => {
+			let session: Session = serde_json::from_str(&saved)?;
+			println!(
+				"resuming session, access token valid for {}s",
+				session.access_valid_for().as_secs()
+			);
+			Client::from_session(session)?
+		}
+		Err(_) => {
+			let credentials = creds_from_file::read_from_file("./credentials.txt").await?;
+			Client::full_login(&credentials).await?
+		}
+	};
+
+	let timetable = client.timetable("2026-02-16", "2026-02-23").await?;
+	println!("{} lessons", timetable.len());
+
+	let session = serde_json::to_string(&client.session())?;
+	tokio::fs::write(path, session).await?;
+
+	Ok(())
+}
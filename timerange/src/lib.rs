@@ -1,4 +1,6 @@
-use chrono::{DateTime, TimeZone};
+use std::ops::Range;
+
+use chrono::{DateTime, NaiveDate, TimeZone};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 /// iterator that splits up time into pieces with a maximum size and optional distance in between
@@ -49,6 +51,41 @@ pub fn range<Tz: TimeZone>(
 	}
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// iterator that splits up a half-open range of days into consecutive half-open ranges of at most `max_days` days \
+/// every chunk ends where the next one starts, so no day is left out or returned twice
+pub struct DateSplit {
+	i: NaiveDate,
+	end: NaiveDate,
+	max_days: u64,
+}
+impl Iterator for DateSplit {
+	type Item = Range<NaiveDate>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.i >= self.end {
+			return None;
+		}
+		let from = self.i;
+		let to = from
+			.checked_add_days(chrono::Days::new(self.max_days))
+			.unwrap_or(self.end)
+			.min(self.end);
+		self.i = to;
+
+		Some(from..to)
+	}
+}
+
+/// creates a new [DateSplit], `max_days` is at least 1
+pub fn date_range(range: Range<NaiveDate>, max_days: u64) -> DateSplit {
+	DateSplit {
+		i: range.start,
+		end: range.end,
+		max_days: max_days.max(1),
+	}
+}
+
 // i'll get back to it whenever
 // combine is proven to work tho which is cool
 
@@ -140,4 +177,24 @@ mod tests {
 		];
 		assert_eq!(timesplit, expected);
 	}
+
+	#[test]
+	fn date_range_chunks() {
+		let date = |s: &str| NaiveDate::from_str(s).unwrap();
+
+		let chunks = date_range(date("2026-02-01")..date("2026-03-15"), 21).collect::<Vec<_>>();
+		let expected = vec![
+			date("2026-02-01")..date("2026-02-22"),
+			date("2026-02-22")..date("2026-03-15"),
+		];
+		assert_eq!(chunks, expected);
+
+		let chunks = date_range(date("2026-02-01")..date("2026-02-03"), 30).collect::<Vec<_>>();
+		assert_eq!(chunks, vec![date("2026-02-01")..date("2026-02-03")]);
+
+		assert_eq!(
+			date_range(date("2026-02-03")..date("2026-02-01"), 30).count(),
+			0
+		);
+	}
 }
@@ -1,68 +1,283 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+	collections::HashMap,
+	sync::Arc,
+	time::{Duration, SystemTime},
+};
 
 use anyhow::anyhow;
-use kreta_rs::{client::Client, login::Credentials};
-use sha2::Digest;
-use tokio::sync::Mutex;
+use argon2::{
+	Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+	password_hash::{SaltString, rand_core::OsRng},
+};
+use kreta_rs::{
+	KretaError,
+	cache::{Cache, MemoryCache, Ttl},
+	client::{Client, class_groups::ClassGroupDetailsRaw, profile::StudentProfileRaw},
+	login::{Credentials, TokensRaw},
+};
+
+use crate::token_store::{MemoryStore, StoredClient, TokenStore};
 
-#[derive(Default)]
 /// essentially a client cache
 pub struct Clients {
-	/// k: username, v: (password hash, client)
-	map: HashMap<String, (Vec<u8>, Arc<Mutex<Client>>)>,
+	/// k: username
+	store: Box<dyn TokenStore>,
+	limits: Limits,
+	/// k: username, v: (wrong passwords in a row, time of the last one)
+	failures: HashMap<String, (u32, SystemTime)>,
+	/// k: username, v: the student's profile and class, so we don't have to query them on every request
+	students: HashMap<String, StudentInfo>,
+	/// the timetable, homework, exams and absences of every user, by username. kept in memory only,
+	/// it's personal data and the sessions are the only thing worth keeping across restarts
+	cache: Cache,
+	/// k: username, v: the tokens of the client as they were last saved to the store
+	synced: HashMap<String, TokensRaw>,
+}
+impl Default for Clients {
+	fn default() -> Self {
+		Self::new(MemoryStore::default(), Limits::default())
+	}
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Limits {
+	/// clients that haven't been used for this long are logged out
+	pub max_idle: Duration,
+	/// if there are more clients than this, the least recently used ones are logged out
+	pub max_clients: usize,
+	/// after this many wrong passwords in a row for a cached user, their requests are refused for `lockout`
+	pub max_failed_attempts: u32,
+	pub lockout: Duration,
+}
+impl Default for Limits {
+	fn default() -> Self {
+		Self {
+			max_idle: Duration::from_secs(60 * 60 * 24 * 14),
+			max_clients: 1024,
+			max_failed_attempts: 5,
+			lockout: Duration::from_secs(60 * 15),
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct StudentInfo {
+	pub profile: StudentProfileRaw,
+	/// the student's class (like 10.A), not the groups they're in
+	pub class: Option<ClassGroupDetailsRaw>,
+}
+impl StudentInfo {
+	pub fn class_name(&self) -> Option<&str> {
+		self.class.as_ref().map(|class| class.name.as_str())
+	}
+	pub fn calendar_name(&self) -> String {
+		timetable_to_ical::calendar_name(&self.profile, self.class_name())
+	}
 }
 impl Clients {
-	/// either uses the saved client from the map, or logs in using the credentials
-	pub async fn client(
-		&mut self,
-		credentials: &Credentials,
-	) -> anyhow::Result<Arc<Mutex<Client>>> {
+	pub fn new(store: impl TokenStore + 'static, limits: Limits) -> Self {
+		let mut clients = Self {
+			store: Box::new(store),
+			limits,
+			failures: HashMap::new(),
+			students: HashMap::new(),
+			cache: Cache::new(MemoryCache::default(), Ttl::default()),
+			synced: HashMap::new(),
+		};
+		// whatever the store loaded is what it has saved
+		clients.remember_synced();
+		clients
+	}
+
+	/// either uses the saved client from the store, or logs in using the credentials
+	pub async fn client(&mut self, credentials: &Credentials) -> anyhow::Result<Arc<Client>> {
 		// println!("{:#?}", self.map.len());
 
 		// println!("retrieving client for {}", credentials.username());
-		let saved = self.map.get(credentials.username()).cloned();
+		self.evict();
+		let saved = self.store.get(credentials.username());
 		// println!("saved: {}", saved.is_some());
-		if let Some((passwd_hash, saved)) = saved {
-			let mut client = saved.lock().await;
+		if let Some(StoredClient {
+			passwd_hash,
+			client,
+			..
+		}) = saved
+		{
+			self.check_lockout(credentials.username())?;
 
 			// check incoming credentials with the ones we have saved, refuse without explanation if they're incorrect
-			let incoming_passwd_hash = hash_password(credentials.passwd());
-			if client.inst_id() != credentials.inst_id() || passwd_hash != incoming_passwd_hash {
-				return Err(anyhow!("invalid credentials"));
+			let passwd_ok = verify_password(credentials.passwd(), &passwd_hash).await;
+			if client.inst_id() != credentials.inst_id() || !passwd_ok {
+				self.record_failure(credentials.username());
+				return Err(KretaError::InvalidCredentials.into());
 			}
+			self.failures.remove(credentials.username());
 
 			let refresh_res = client.refresh_if_needed().await;
-			// if refresh token fails just log in again
+			// if the refresh token has expired just log in again,
+			// but if kreta is down logging in won't help either
 			match refresh_res {
 				Ok(_) => {
-					return Ok(saved.clone());
+					self.store.touch(credentials.username());
+					self.sync_if_refreshed(credentials.username());
+					return Ok(client);
 				}
-				Err(err) => {
+				Err(err) if err.is_auth() => {
 					eprintln!("failed to use saved client: {err}\ndefaulting to logging in again");
 				}
+				Err(err) => return Err(err.into()),
 			}
 		}
 
 		let client = Client::full_login(credentials).await?;
-		let client = Arc::new(Mutex::new(client));
-		let passwd_hash = hash_password(credentials.passwd());
+		let client = Arc::new(client);
+		let passwd_hash = hash_password(credentials.passwd()).await?;
 
-		self.map
-			.insert(credentials.username().into(), (passwd_hash, client.clone()));
+		let entry = StoredClient {
+			passwd_hash,
+			client: client.clone(),
+			last_used: SystemTime::now(),
+		};
+		self.store.insert(credentials.username(), entry);
 		// println!("just saved client for {}", credentials.username());
+
+		self.remove_idle();
+		self.sync();
 		Ok(client)
 	}
+
+	/// logs out the clients that have been idle for too long, then the least recently used ones if there's still too many \
+	/// runs on every [Clients::client] call
+	pub fn evict(&mut self) {
+		if self.remove_idle() {
+			self.sync();
+		}
+	}
+	/// [Clients::evict] without saving, true if anyone was logged out
+	fn remove_idle(&mut self) -> bool {
+		let mut removed = false;
+		let now = SystemTime::now();
+		let mut last_used = self.store.last_used();
+		last_used.sort_by_key(|(_, last_used)| std::cmp::Reverse(*last_used));
+
+		for (i, (username, last_used)) in last_used.into_iter().enumerate() {
+			let idle = now.duration_since(last_used).unwrap_or_default();
+			if idle > self.limits.max_idle || i >= self.limits.max_clients {
+				self.store.remove(&username);
+				self.students.remove(&username);
+				self.failures.remove(&username);
+				self.cache.remove_user(&username);
+				self.synced.remove(&username);
+				removed = true;
+			}
+		}
+		removed
+	}
+
+	/// refuses the request if there were too many wrong passwords for the user recently
+	fn check_lockout(&mut self, username: &str) -> anyhow::Result<()> {
+		let Some((count, last)) = self.failures.get(username).copied() else {
+			return Ok(());
+		};
+		if count < self.limits.max_failed_attempts {
+			return Ok(());
+		}
+
+		let since = SystemTime::now().duration_since(last).unwrap_or_default();
+		match self.limits.lockout.checked_sub(since) {
+			Some(remaining) if !remaining.is_zero() => Err(anyhow!(
+				"too many wrong passwords for {username}, try again in {} minutes",
+				remaining.as_secs().div_ceil(60)
+			)),
+			_ => {
+				// the lockout is over, start counting again
+				self.failures.remove(username);
+				Ok(())
+			}
+		}
+	}
+	fn record_failure(&mut self, username: &str) {
+		let entry = self
+			.failures
+			.entry(username.into())
+			.or_insert((0, SystemTime::now()));
+		entry.0 += 1;
+		entry.1 = SystemTime::now();
+	}
+
+	/// saves the sessions if `username`'s client has different tokens than when they were last saved \
+	/// clients refresh their tokens on their own when kreta answers 401, so call this after using one
+	pub fn sync_if_refreshed(&mut self, username: &str) {
+		let Some(stored) = self.store.get(username) else {
+			return;
+		};
+		if self.synced.get(username) != Some(&stored.client.session().tokens) {
+			self.sync();
+		}
+	}
+
+	/// errors are only printed, failing to save the sessions shouldn't fail the request
+	fn sync(&mut self) {
+		match self.store.sync() {
+			Ok(_) => self.remember_synced(),
+			Err(err) => eprintln!("failed to save sessions: {err:?}"),
+		}
+	}
+	fn remember_synced(&mut self) {
+		self.synced = self
+			.store
+			.last_used()
+			.into_iter()
+			.filter_map(|(username, _)| {
+				let tokens = self.store.get(&username)?.client.session().tokens;
+				Some((username, tokens))
+			})
+			.collect();
+	}
+}
+
+impl Clients {
+	/// use with [Cache::with] and the username, only after [Clients::client] succeeded for the same user
+	pub fn cache(&self) -> Cache {
+		self.cache.clone()
+	}
+
+	/// only call after [Clients::client] succeeded for the same user, this doesn't check credentials
+	pub fn cached_student_info(&self, username: &str) -> Option<StudentInfo> {
+		self.students.get(username).cloned()
+	}
+	pub fn cache_student_info(&mut self, username: &str, info: StudentInfo) {
+		self.students.insert(username.into(), info);
+	}
+}
+
+/// a salted argon2 hash of the password, in PHC string format (salt and parameters included) \
+/// it's only ever checked against the incoming password of clients that have already authenticated successfully
+async fn hash_password(passwd: &str) -> anyhow::Result<String> {
+	let passwd = passwd.to_string();
+	// argon2 is slow on purpose, don't block the executor with it
+	tokio::task::spawn_blocking(move || {
+		let salt = SaltString::generate(&mut OsRng);
+		let hash = Argon2::default()
+			.hash_password(passwd.as_bytes(), &salt)
+			.map_err(|err| anyhow!("failed to hash password: {err}"))?;
+		anyhow::Ok(hash.to_string())
+	})
+	.await?
 }
 
-/// this would be an awful password hash function to use for any proper authentication service.
-/// the reason i think it's probably fine is because it only stays in memory and only ever checked against the incoming password
-/// of clients that have already previously authenticated successfully \
-/// but still i'm happy to accept pull requests or whatever if memory security of self-hosted, (most likely) single-user apps is your thing
-fn hash_password(passwd: &str) -> Vec<u8> {
-	let mut hasher = sha2::Sha256::default();
-	for _ in 0..42 {
-		hasher.update(passwd);
-	}
-	let res = hasher.finalize();
-	res.to_vec()
+/// argon2 compares the hashes in constant time
+async fn verify_password(passwd: &str, hash: &str) -> bool {
+	let passwd = passwd.to_string();
+	let hash = hash.to_string();
+	tokio::task::spawn_blocking(move || {
+		let Ok(hash) = PasswordHash::new(&hash) else {
+			return false;
+		};
+		Argon2::default()
+			.verify_password(passwd.as_bytes(), &hash)
+			.is_ok()
+	})
+	.await
+	.unwrap_or(false)
 }
@@ -1,7 +1,12 @@
+use std::sync::LazyLock;
+
 use actix_web::{App, HttpServer, web};
 use tokio::sync::Mutex;
 
-use crate::clients::Clients;
+use crate::{
+	clients::{Clients, Limits},
+	token_store::EncryptedFileStore,
+};
 
 #[cfg(feature = "combine")]
 pub mod combine;
@@ -10,16 +15,30 @@ pub mod clients;
 pub mod k8;
 pub mod landing;
 pub mod timetables;
+pub mod token_store;
 
 const BIND: (&str, u16) = const {
 	let port = if cfg!(debug_assertions) { 8080 } else { 18080 };
 	("0.0.0.0", port)
 };
+/// the domain the server is reachable on, from `TIMETABLE_DOMAIN` \
+/// goes after the @ in every event's uid, so it shouldn't change once people have subscribed
+pub static UID_DOMAIN: LazyLock<String> = LazyLock::new(|| {
+	std::env::var("TIMETABLE_DOMAIN").unwrap_or_else(|_| "timetable-to-ical".into())
+});
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-	let signer = credsign::load_or_create(std::env::current_dir()?).await?;
+	let cwd = std::env::current_dir()?;
+	let signer = credsign::load_or_create(&cwd).await?;
+
+	// sessions are kept across restarts so we don't have to log everyone in again after every deploy
+	let store = EncryptedFileStore::load_or_create(cwd.join(".sessions"), signer.clone());
+	let mut clients = Clients::new(store, Limits::default());
+	clients.evict();
+
 	let signer = web::Data::new(signer);
-	let clients = web::Data::new(Mutex::new(Clients::default()));
+	let clients = web::Data::new(Mutex::new(clients));
 
 	let server = HttpServer::new(move || {
 		App::new()
@@ -31,6 +50,7 @@ async fn main() -> anyhow::Result<()> {
 			.service(timetables::login_k8::timetable_k8)
 			.service(timetables::login_k8::combine_k8)
 			.service(timetables::login_k8::absences_k8)
+			.service(timetables::login_k8::grades_k8)
 			.service(k8::create_k8)
 	})
 	.bind(BIND)?
@@ -0,0 +1,46 @@
+//! how the server reacts to the different kinds of errors kreta can give us
+
+use actix_web::{HttpResponse, http::StatusCode};
+use kreta_rs::KretaError;
+use timetable_to_ical::{Options, err::result_as_timetable};
+
+/// the status code to answer with if a request fails with `err`
+pub fn error_status(err: &anyhow::Error) -> StatusCode {
+	match KretaError::find(err) {
+		Some(KretaError::InvalidCredentials) => StatusCode::UNAUTHORIZED,
+		Some(err) if err.is_temporary() => StatusCode::SERVICE_UNAVAILABLE,
+		_ => StatusCode::NOT_ACCEPTABLE,
+	}
+}
+
+/// turns the result of a calendar request into a response \
+/// if kreta is down or rate limiting us we answer with a 503, so calendar apps keep the events they already have
+/// instead of replacing the whole calendar with an error event. every other error still ends up in the calendar
+pub fn calendar_response(res: anyhow::Result<String>) -> HttpResponse {
+	if let Err(err) = &res
+		&& let Some(kreta_err) = KretaError::find(err)
+		&& kreta_err.is_temporary()
+	{
+		eprintln!("kreta is unavailable, not touching the calendar: {err}");
+
+		let mut resp = HttpResponse::ServiceUnavailable();
+		if let KretaError::RateLimited {
+			retry_after: Some(retry_after),
+			..
+		} = kreta_err
+		{
+			resp.insert_header(("Retry-After", retry_after.as_secs().to_string()));
+		}
+		return resp.body(format!("{err}"));
+	}
+
+	// the options might be what failed to parse, the error event only needs the uid domain anyway
+	let opts = Options {
+		uid_domain: crate::UID_DOMAIN.as_str().into(),
+		..Default::default()
+	};
+	let timetable = result_as_timetable(res, &opts);
+	HttpResponse::Ok()
+		.content_type("text/calendar")
+		.body(timetable)
+}
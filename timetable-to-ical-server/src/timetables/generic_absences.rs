@@ -3,7 +3,7 @@ use anyhow::Context;
 use kreta_rs::login::Credentials;
 use tokio::sync::Mutex;
 
-use crate::clients::Clients;
+use crate::{clients::Clients, timetables::student_info};
 
 /// not really a timetable implementation this returns html
 pub async fn generic_absences(
@@ -12,14 +12,14 @@ pub async fn generic_absences(
 ) -> anyhow::Result<Html> {
 	#[cfg(feature = "absence-analyzer")]
 	{
-		let absences_raw = {
-			let client = {
+		let (absences_raw, info) = {
+			let (client, cache) = {
 				let mut clients = clients.lock().await;
-				clients.client(credentials).await?
+				(clients.client(credentials).await?, clients.cache())
 			};
-			let client = client.lock().await;
 
-			let absences = absence_analyzer::retreive::fetch_absences(&client)
+			let cached = cache.with(&client, credentials.username());
+			let absences = absence_analyzer::retreive::fetch_absences_cached(&cached)
 				.await
 				.with_context(|| {
 					format!(
@@ -27,11 +27,16 @@ pub async fn generic_absences(
 						credentials.username()
 					)
 				})?;
+			let info = student_info(credentials, &clients, &client).await;
 
-			absences
+			(absences, info)
 		};
 
-		let html = absence_analyzer::html_stats::html_stats(&absences_raw);
+		let html = absence_analyzer::html_stats::html_stats_with_profile(
+			&absences_raw,
+			info.as_ref().map(|info| &info.profile),
+			info.as_ref().and_then(|info| info.class_name()),
+		);
 
 		Ok(Html::new(html))
 	}
@@ -3,7 +3,10 @@ use kreta_rs::login::Credentials;
 use timetable_to_ical::Options;
 use tokio::sync::Mutex;
 
-use crate::{clients::Clients, timetables::range_3w_3w};
+use crate::{
+	clients::{Clients, StudentInfo},
+	timetables::{range_3w_3w, student_info},
+};
 
 /// generic implementation for the timetable variation that combines timetable, exams and homeworks to build the timetable
 pub async fn generic_combine(
@@ -11,19 +14,27 @@ pub async fn generic_combine(
 	clients: web::Data<Mutex<Clients>>,
 	opts: &Options,
 ) -> anyhow::Result<String> {
-	let client = {
+	let (client, cache) = {
 		let mut clients = clients.lock().await;
-		clients.client(credentials).await?
+		(clients.client(credentials).await?, clients.cache())
 	};
-	let client = client.lock().await;
 
-	let (start, end) = range_3w_3w();
+	let range = range_3w_3w();
 
 	let timetable = {
 		#[cfg(feature = "combine")]
 		{
-			timetable_to_ical::combine::combined_range_calendar_file(&client, start, end, opts)
-				.await?
+			let info = student_info(credentials, &clients, &client).await;
+			let calendar_name = info.as_ref().map(StudentInfo::calendar_name);
+
+			let cached = cache.with(&client, credentials.username());
+			timetable_to_ical::combine::combined_cached_calendar_file(
+				&cached,
+				range,
+				opts,
+				calendar_name.as_deref(),
+			)
+			.await?
 		}
 
 		#[cfg(not(feature = "combine"))]
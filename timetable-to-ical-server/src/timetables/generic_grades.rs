@@ -0,0 +1,63 @@
+use actix_web::web::{self, Html};
+use anyhow::Context;
+use kreta_rs::login::Credentials;
+use tokio::sync::Mutex;
+
+use crate::{clients::Clients, timetables::student_info};
+
+/// not really a timetable implementation this returns html
+pub async fn generic_grades(
+	credentials: &Credentials,
+	clients: web::Data<Mutex<Clients>>,
+) -> anyhow::Result<Html> {
+	#[cfg(feature = "grade-analyzer")]
+	{
+		let (grades_raw, info, class_averages) = {
+			let client = {
+				let mut clients = clients.lock().await;
+				clients.client(credentials).await?
+			};
+
+			let grades = grade_analyzer::retreive::fetch_grades(&client)
+				.await
+				.with_context(|| {
+					format!(
+						"failed to fetch every grade for user {}",
+						credentials.username()
+					)
+				})?;
+			let info = student_info(credentials, &clients, &client).await;
+
+			// comparing with the class is a nice to have, errors shouldn't stop the page from loading
+			let class = info.as_ref().and_then(|info| info.class.as_ref());
+			let class_averages = match class {
+				Some(class) => client.class_averages_of(class).await.unwrap_or_else(|err| {
+					eprintln!(
+						"failed to query class averages for {}: {err:?}",
+						credentials.username()
+					);
+					Vec::new()
+				}),
+				None => Vec::new(),
+			};
+
+			(grades, info, class_averages)
+		};
+
+		let html = grade_analyzer::html_stats::html_stats_with_profile(
+			&grades_raw,
+			info.as_ref().map(|info| &info.profile),
+			info.as_ref().and_then(|info| info.class_name()),
+			&class_averages,
+		);
+
+		Ok(Html::new(html))
+	}
+
+	#[cfg(not(feature = "grade-analyzer"))]
+	{
+		Ok(Html::new(
+			"grade-analyzer wasn't enabled on this server at compile time",
+		))
+	}
+}
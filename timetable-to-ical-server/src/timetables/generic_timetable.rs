@@ -3,7 +3,10 @@ use kreta_rs::login::Credentials;
 use timetable_to_ical::Options;
 use tokio::sync::Mutex;
 
-use crate::{clients::Clients, timetables::one_month_range};
+use crate::{
+	clients::{Clients, StudentInfo},
+	timetables::{one_month_range, student_info},
+};
 
 /// basic timetable implentation for generic, single query timetable requests
 pub async fn generic_timetable(
@@ -15,12 +18,14 @@ pub async fn generic_timetable(
 		let mut clients = clients.lock().await;
 		clients.client(credentials).await?
 	};
-	let client = client.lock().await;
 
 	let (start, end) = one_month_range();
 	let timetable = client.timetable(&start, &end).await?;
+	let info = student_info(credentials, &clients, &client).await;
+	let calendar_name = info.as_ref().map(StudentInfo::calendar_name);
 
-	let timetable = timetable_to_ical::lessons_to_calendar_file(&timetable, opts);
+	let timetable =
+		timetable_to_ical::lessons_to_calendar_file(&timetable, opts, calendar_name.as_deref());
 
 	anyhow::Ok(timetable)
 }
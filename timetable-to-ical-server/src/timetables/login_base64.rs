@@ -1,11 +1,10 @@
-use actix_web::{HttpResponse, Responder, get, web};
+use actix_web::{Responder, get, web};
 use anyhow::Context;
-use timetable_to_ical::err::handle_timetable_err_async;
 use tokio::sync::Mutex;
 
 use crate::{
 	clients::Clients,
-	timetables::{OptsParams, generic_timetable},
+	timetables::{OptsParams, calendar_response, generic_timetable},
 };
 
 #[get("/base64/{blob}/timetable.ical")]
@@ -14,19 +13,19 @@ pub async fn timetable_base64(
 	clients: web::Data<Mutex<Clients>>,
 	opts: web::Query<OptsParams>,
 ) -> impl Responder {
-	let timetable = handle_timetable_err_async(async move {
+	let res = async move {
 		let base64 = path.into_inner();
 		let credentials = crate::k8::decode_base64(&base64)
 			.with_context(|| format!("failed to decode base64 {base64}"))?;
 
 		let opts = opts.extract_options()?;
 
-		let timetable = generic_timetable(&credentials, clients, &opts).await?;
+		let timetable = generic_timetable(&credentials, clients.clone(), &opts).await;
+		super::sync_if_refreshed(&credentials, &clients).await;
+		let timetable = timetable?;
 		anyhow::Ok(timetable)
-	})
+	}
 	.await;
 
-	HttpResponse::Ok()
-		.content_type("text/calendar")
-		.body(timetable)
+	calendar_response(res)
 }
@@ -1,10 +1,12 @@
 use actix_web::{Either, HttpResponse, Responder, get, web};
 use anyhow::Context;
 use credsign::Signer;
-use timetable_to_ical::err::handle_timetable_err_async;
 use tokio::sync::Mutex;
 
-use crate::{clients::Clients, timetables::OptsParams};
+use crate::{
+	clients::Clients,
+	timetables::{OptsParams, calendar_response, error_status},
+};
 
 #[get("/k8/{k8}/combine.ical")]
 pub async fn combine_k8(
@@ -13,21 +15,21 @@ pub async fn combine_k8(
 	clients: web::Data<Mutex<Clients>>,
 	opts: web::Query<OptsParams>,
 ) -> impl Responder {
-	let timetable = handle_timetable_err_async(async move {
+	let res = async move {
 		let k8 = k8.into_inner();
 		let credentials = crate::k8::decode_k8(&k8, &signer)
 			.with_context(|| format!("failed to decode k8 {k8}"))?;
 
 		let opts = opts.extract_options()?;
 
-		let timetable = super::generic_combine(&credentials, clients, &opts).await?;
+		let timetable = super::generic_combine(&credentials, clients.clone(), &opts).await;
+		super::sync_if_refreshed(&credentials, &clients).await;
+		let timetable = timetable?;
 		anyhow::Ok(timetable)
-	})
+	}
 	.await;
 
-	HttpResponse::Ok()
-		.content_type("text/calendar")
-		.body(timetable)
+	calendar_response(res)
 }
 
 #[get("/k8/{k8}/timetable.ical")]
@@ -37,21 +39,21 @@ pub async fn timetable_k8(
 	clients: web::Data<Mutex<Clients>>,
 	opts: web::Query<OptsParams>,
 ) -> impl Responder {
-	let timetable = handle_timetable_err_async(async move {
+	let res = async move {
 		let k8 = k8.into_inner();
 		let credentials = crate::k8::decode_k8(&k8, &signer)
 			.with_context(|| format!("failed to decode k8 {k8}"))?;
 
 		let opts = opts.extract_options()?;
 
-		let timetable = super::generic_timetable(&credentials, clients, &opts).await?;
+		let timetable = super::generic_timetable(&credentials, clients.clone(), &opts).await;
+		super::sync_if_refreshed(&credentials, &clients).await;
+		let timetable = timetable?;
 		anyhow::Ok(timetable)
-	})
+	}
 	.await;
 
-	HttpResponse::Ok()
-		.content_type("text/calendar")
-		.body(timetable)
+	calendar_response(res)
 }
 
 #[get("/k8/{k8}/absences.html")]
@@ -65,7 +67,38 @@ pub async fn absences_k8(
 		let credentials = crate::k8::decode_k8(&k8, &signer)
 			.with_context(|| format!("failed to decode k8 {k8}"))?;
 
-		let html = super::generic_absences(&credentials, clients.clone()).await?;
+		let html = super::generic_absences(&credentials, clients.clone()).await;
+		super::sync_if_refreshed(&credentials, &clients).await;
+		let html = html?;
+		anyhow::Ok(html)
+	};
+	let f = f().await;
+
+	match f {
+		Ok(a) => Either::Left(a),
+		Err(err) => {
+			let resp = HttpResponse::build(error_status(&err))
+				.content_type("text/html")
+				.body(format!("{err}"));
+			Either::Right(resp)
+		}
+	}
+}
+
+#[get("/k8/{k8}/grades.html")]
+pub async fn grades_k8(
+	k8: web::Path<String>,
+	signer: web::Data<Signer>,
+	clients: web::Data<Mutex<Clients>>,
+) -> impl Responder {
+	let f = async || {
+		let k8 = k8.into_inner();
+		let credentials = crate::k8::decode_k8(&k8, &signer)
+			.with_context(|| format!("failed to decode k8 {k8}"))?;
+
+		let html = super::generic_grades(&credentials, clients.clone()).await;
+		super::sync_if_refreshed(&credentials, &clients).await;
+		let html = html?;
 		anyhow::Ok(html)
 	};
 	let f = f().await;
@@ -73,7 +106,7 @@ pub async fn absences_k8(
 	match f {
 		Ok(a) => Either::Left(a),
 		Err(err) => {
-			let resp = HttpResponse::NotAcceptable()
+			let resp = HttpResponse::build(error_status(&err))
 				.content_type("text/html")
 				.body(format!("{err}"));
 			Either::Right(resp)
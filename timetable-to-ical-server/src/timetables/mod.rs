@@ -7,14 +7,78 @@ pub use generic_combine::generic_combine;
 
 pub mod generic_absences;
 pub use generic_absences::generic_absences;
+pub mod generic_grades;
+pub use generic_grades::generic_grades;
 
 pub mod login_base64;
 pub mod login_k8;
 
+pub mod errors;
+pub use errors::{calendar_response, error_status};
+
 pub mod utils_receive_opts;
 pub use utils_receive_opts::OptsParams;
 
-use chrono::{DateTime, NaiveDateTime, NaiveTime, Utc};
+use actix_web::web;
+use chrono::Utc;
+use kreta_rs::{
+	client::{Client, class_groups::current_class},
+	login::Credentials,
+};
+use tokio::sync::Mutex;
+
+use crate::clients::{Clients, StudentInfo};
+
+/// the logged in student's profile and class, only queried from kreta on the first request of every user \
+/// it's only used to make things look nicer so errors are printed and otherwise ignored
+async fn student_info(
+	credentials: &Credentials,
+	clients: &web::Data<Mutex<Clients>>,
+	client: &Client,
+) -> Option<StudentInfo> {
+	{
+		let clients = clients.lock().await;
+		if let Some(info) = clients.cached_student_info(credentials.username()) {
+			return Some(info);
+		}
+	}
+
+	let (profile, class_groups) = tokio::join!(client.student_profile(), client.class_groups());
+	let class = match class_groups {
+		Ok(groups) => current_class(&groups).cloned(),
+		Err(err) => {
+			eprintln!(
+				"failed to query class groups for {}: {err:?}",
+				credentials.username()
+			);
+			None
+		}
+	};
+
+	match profile {
+		Ok(profile) => {
+			let info = StudentInfo { profile, class };
+
+			let mut clients = clients.lock().await;
+			clients.cache_student_info(credentials.username(), info.clone());
+			Some(info)
+		}
+		Err(err) => {
+			eprintln!(
+				"failed to query student profile for {}: {err:?}",
+				credentials.username()
+			);
+			None
+		}
+	}
+}
+
+/// saves the sessions if the client of `credentials` refreshed its tokens while handling the request,
+/// so a restart doesn't leave us with a refresh token kreta has already replaced
+async fn sync_if_refreshed(credentials: &Credentials, clients: &web::Data<Mutex<Clients>>) {
+	let mut clients = clients.lock().await;
+	clients.sync_if_refreshed(credentials.username());
+}
 
 /// one month range centered on today
 fn one_month_range_base() -> (chrono::NaiveDate, chrono::NaiveDate) {
@@ -34,20 +98,11 @@ fn one_month_range() -> (String, String) {
 	)
 }
 
-fn range_3w_3w() -> (chrono::DateTime<Utc>, chrono::DateTime<Utc>) {
-	let (start, end) = {
-		let today = Utc::now().date_naive();
-		let start = today - chrono::Duration::weeks(3);
-		let end = today + chrono::Duration::weeks(3);
-		(start, end)
-	};
-	let time = NaiveTime::from_hms_opt(0, 0, 0).expect("from_hms_opt(0, 0, 0) failed");
-
-	let start = NaiveDateTime::new(start, time);
-	let end = NaiveDateTime::new(end, time);
-
-	let start = DateTime::from_naive_utc_and_offset(start, Utc);
-	let end = DateTime::from_naive_utc_and_offset(end, Utc);
+/// 3 weeks before and after today, half-open
+fn range_3w_3w() -> std::ops::Range<chrono::NaiveDate> {
+	let today = Utc::now().date_naive();
+	let start = today - chrono::Duration::weeks(3);
+	let end = today + chrono::Duration::weeks(3);
 
-	(start, end)
+	start..end
 }
@@ -9,8 +9,10 @@ pub struct OptsParams {
 	opts: Option<String>,
 }
 impl OptsParams {
+	/// the options in ?opts=, or the defaults if there aren't any \
+	/// the uid domain is always ours, see [UID_DOMAIN](crate::UID_DOMAIN)
 	pub fn extract_options(&self) -> anyhow::Result<Options> {
-		match &self.opts {
+		let mut opts: Options = match &self.opts {
 			Some(base64) => {
 				let blob = base64::prelude::BASE64_URL_SAFE
 					.decode(base64)
@@ -18,12 +20,13 @@ impl OptsParams {
 				let blob = String::from_utf8(blob)
 					.with_context(|| "base64 encoded blob provided as ?opts= is not utf-8")?;
 
-				let decoded: Options = serde_json::from_str(&blob)
-					.with_context(|| "while deserializing json options provided as ?opts=")?;
-
-				Ok(decoded)
+				serde_json::from_str(&blob)
+					.with_context(|| "while deserializing json options provided as ?opts=")?
 			}
-			None => Ok(Default::default()),
-		}
+			None => Default::default(),
+		};
+		opts.uid_domain = crate::UID_DOMAIN.as_str().into();
+
+		Ok(opts)
 	}
 }
@@ -0,0 +1,260 @@
+//! where [Clients](crate::clients::Clients) keeps the logged in clients between requests (and restarts)
+
+use std::{
+	collections::HashMap,
+	io::Write,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use credsign::Signer;
+use kreta_rs::{client::Client, login::Session};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone)]
+pub struct StoredClient {
+	/// the hash of the password the client logged in with, incoming credentials are checked against this
+	pub passwd_hash: String,
+	pub client: Arc<Client>,
+	pub last_used: SystemTime,
+}
+
+pub trait TokenStore: Send {
+	fn get(&self, username: &str) -> Option<StoredClient>;
+	fn insert(&mut self, username: &str, entry: StoredClient);
+	fn remove(&mut self, username: &str);
+	/// marks the client as used just now, so it doesn't get evicted
+	fn touch(&mut self, username: &str);
+
+	/// every saved username with the time it was last used
+	fn last_used(&self) -> Vec<(String, SystemTime)>;
+
+	/// called whenever the tokens of a client might have changed (login, refresh, eviction),
+	/// persistent stores should save everything here
+	fn sync(&mut self) -> anyhow::Result<()> {
+		Ok(())
+	}
+}
+
+#[derive(Default)]
+/// forgets everything on restart
+pub struct MemoryStore {
+	map: HashMap<String, StoredClient>,
+}
+impl TokenStore for MemoryStore {
+	fn get(&self, username: &str) -> Option<StoredClient> {
+		self.map.get(username).cloned()
+	}
+	fn insert(&mut self, username: &str, entry: StoredClient) {
+		self.map.insert(username.into(), entry);
+	}
+	fn remove(&mut self, username: &str) {
+		self.map.remove(username);
+	}
+	fn touch(&mut self, username: &str) {
+		if let Some(entry) = self.map.get_mut(username) {
+			entry.last_used = SystemTime::now();
+		}
+	}
+
+	fn last_used(&self) -> Vec<(String, SystemTime)> {
+		self.map
+			.iter()
+			.map(|(username, entry)| (username.clone(), entry.last_used))
+			.collect()
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// a [StoredClient] as it's saved to disk
+struct SavedClient {
+	username: String,
+	passwd_hash: String,
+	session: Session,
+	/// seconds since the unix epoch
+	last_used: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+/// entries saved by older versions (like before passwords were hashed with argon2) might not deserialize anymore,
+/// those users are logged out instead of the whole file failing to load
+enum SavedEntry {
+	Client(SavedClient),
+	Unknown { username: Option<String> },
+}
+
+/// a [MemoryStore] that's saved to disk after every change, encrypted with the same key as k8s \
+/// the sessions contain refresh tokens, so they shouldn't be lying around in plain text
+pub struct EncryptedFileStore {
+	memory: MemoryStore,
+	path: PathBuf,
+	signer: Signer,
+	/// incremented on every sync, so an older snapshot never overwrites a newer one
+	generation: u64,
+	/// the generation that's on disk right now
+	written: Arc<Mutex<u64>>,
+}
+impl EncryptedFileStore {
+	/// loads the saved clients from `path` if it exists \
+	/// if it can't be read (it's corrupt, or the key changed), it's moved aside and everyone has to log in again
+	pub fn load_or_create(path: impl Into<PathBuf>, signer: Signer) -> Self {
+		let path = path.into();
+		let mut memory = MemoryStore::default();
+
+		let saved = match load(&path, &signer) {
+			Ok(saved) => saved,
+			Err(err) => {
+				eprintln!("failed to load the saved sessions, starting without them: {err:?}");
+				move_aside(&path);
+				Vec::new()
+			}
+		};
+		for saved in saved {
+			let saved = match saved {
+				SavedEntry::Client(saved) => saved,
+				SavedEntry::Unknown { username } => {
+					let username = username.as_deref().unwrap_or("an unknown user");
+					eprintln!(
+						"dropping the saved session of {username}, it's in an outdated format"
+					);
+					continue;
+				}
+			};
+			let client = match Client::from_session(saved.session) {
+				Ok(client) => client,
+				Err(err) => {
+					eprintln!("failed to restore the session of {}: {err}", saved.username);
+					continue;
+				}
+			};
+			let entry = StoredClient {
+				passwd_hash: saved.passwd_hash,
+				client: Arc::new(client),
+				last_used: UNIX_EPOCH + Duration::from_secs(saved.last_used),
+			};
+			memory.insert(&saved.username, entry);
+		}
+
+		Self {
+			memory,
+			path,
+			signer,
+			generation: 0,
+			written: Default::default(),
+		}
+	}
+}
+
+/// the saved clients in `path`, or none if there's no such file
+fn load(path: &Path, signer: &Signer) -> anyhow::Result<Vec<SavedEntry>> {
+	let file = match std::fs::read_to_string(path) {
+		Ok(file) => file,
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+		Err(err) => Err(err).with_context(|| format!("while reading {}", path.display()))?,
+	};
+	let json = signer
+		.decrypt_text(file.trim())
+		.with_context(|| format!("while decrypting {}", path.display()))?;
+	let saved = serde_json::from_str(&json)
+		.with_context(|| format!("while deserializing {}", path.display()))?;
+	Ok(saved)
+}
+
+/// renames `path` to `{path}.broken-{unix time}`, so it's not loaded again but it's still there to look at
+fn move_aside(path: &Path) {
+	let now = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_secs();
+	let mut aside = path.as_os_str().to_owned();
+	aside.push(format!(".broken-{now}"));
+
+	match std::fs::rename(path, &aside) {
+		Ok(_) => eprintln!("moved {} to {}", path.display(), aside.display()),
+		Err(err) => eprintln!("failed to move {} aside: {err}", path.display()),
+	}
+}
+
+/// writes `contents` to a temporary file next to `path` and renames it over `path`,
+/// so a crash halfway through never leaves a half written file behind
+fn write_atomic(path: &Path, contents: &str) -> anyhow::Result<()> {
+	let mut tmp = path.as_os_str().to_owned();
+	tmp.push(".tmp");
+	let tmp = PathBuf::from(tmp);
+
+	let mut file =
+		std::fs::File::create(&tmp).with_context(|| format!("while creating {}", tmp.display()))?;
+	file.write_all(contents.as_bytes())
+		.and_then(|_| file.sync_all())
+		.with_context(|| format!("while writing {}", tmp.display()))?;
+	std::fs::rename(&tmp, path)
+		.with_context(|| format!("while renaming {} to {}", tmp.display(), path.display()))?;
+	Ok(())
+}
+
+impl TokenStore for EncryptedFileStore {
+	fn get(&self, username: &str) -> Option<StoredClient> {
+		self.memory.get(username)
+	}
+	fn insert(&mut self, username: &str, entry: StoredClient) {
+		self.memory.insert(username, entry);
+	}
+	fn remove(&mut self, username: &str) {
+		self.memory.remove(username);
+	}
+	fn touch(&mut self, username: &str) {
+		self.memory.touch(username);
+	}
+	fn last_used(&self) -> Vec<(String, SystemTime)> {
+		self.memory.last_used()
+	}
+
+	fn sync(&mut self) -> anyhow::Result<()> {
+		let saved = self
+			.memory
+			.map
+			.iter()
+			.map(|(username, entry)| SavedClient {
+				username: username.clone(),
+				passwd_hash: entry.passwd_hash.clone(),
+				session: entry.client.session(),
+				last_used: entry
+					.last_used
+					.duration_since(UNIX_EPOCH)
+					.unwrap_or_default()
+					.as_secs(),
+			})
+			.collect::<Vec<_>>();
+
+		let json = serde_json::to_string(&saved)?;
+		let encrypted = self.signer.encrypt_text(&json)?;
+
+		self.generation += 1;
+		let generation = self.generation;
+		let written = self.written.clone();
+		let path = self.path.clone();
+		let write = move || {
+			let mut written = written.lock().unwrap_or_else(|err| err.into_inner());
+			// a newer snapshot got here first
+			if *written > generation {
+				return;
+			}
+			match write_atomic(&path, &encrypted) {
+				Ok(_) => *written = generation,
+				Err(err) => eprintln!("failed to save sessions: {err:?}"),
+			}
+		};
+
+		// this is called with the clients locked, don't make everyone wait for the disk
+		match tokio::runtime::Handle::try_current() {
+			Ok(runtime) => {
+				runtime.spawn_blocking(write);
+			}
+			Err(_) => write(),
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,81 @@
+//! CATEGORIES and COLOR of the events, so calendar apps can filter and color them natively
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use ics::properties::{Categories, Color};
+use kreta_rs::client::timetable::SubjectRaw;
+
+/// the category of lessons with an announced exam and of the exams themselves
+pub const EXAM: &str = "Számonkérés";
+/// the category of lessons with homework due and of the homework itself
+pub const HOMEWORK: &str = "Házi feladat";
+pub const SUBSTITUTION: &str = "Helyettesítés";
+
+/// COLOR has to be a css3 color name (rfc7986), these are the ones that are readable on both light & dark backgrounds
+const COLORS: &[&str] = &[
+	"crimson",
+	"darkorange",
+	"goldenrod",
+	"olivedrab",
+	"seagreen",
+	"teal",
+	"steelblue",
+	"royalblue",
+	"slateblue",
+	"darkviolet",
+	"mediumvioletred",
+	"sienna",
+	"cadetblue",
+	"indianred",
+	"darkcyan",
+	"peru",
+];
+
+/// the same subject always gets the same color \
+/// hashed the same way as absence-analyzer's `hash_to_color`, but onto one of [COLORS]
+pub fn subject_color(subject: &SubjectRaw) -> &'static str {
+	let mut hasher = DefaultHasher::new();
+	subject.uid.hash(&mut hasher);
+	let hash = hasher.finish();
+
+	COLORS[(hash % COLORS.len() as u64) as usize]
+}
+
+pub(crate) fn color<'a>(subject: &SubjectRaw) -> Color<'a> {
+	Color::new(subject_color(subject))
+}
+
+/// None if there aren't any
+pub(crate) fn categories<'a>(categories: &[&str]) -> Option<Categories<'a>> {
+	let categories = categories
+		.iter()
+		.filter(|category| !category.is_empty())
+		.map(|category| crate::escape_desc_text(category))
+		.collect::<Vec<_>>();
+
+	match categories.len() {
+		0 => None,
+		_ => Some(Categories::new(categories.join(","))),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ics::Event;
+
+	use super::*;
+	use crate::tests::property;
+
+	#[test]
+	fn categories_are_escaped_and_empty_ones_dropped() {
+		assert!(categories(&[]).is_none());
+		assert!(categories(&["", ""]).is_none());
+
+		let mut event = Event::new("uid", "dtstamp");
+		event.push(categories(&["", "matematika, algebra", HOMEWORK]).unwrap());
+		assert_eq!(
+			property(&event.to_string(), "CATEGORIES"),
+			"matematika\\, algebra,Házi feladat"
+		);
+	}
+}
@@ -1,28 +1,32 @@
+use std::collections::{HashMap, HashSet};
+
 use anyhow::Context;
-use chrono::{DateTime, Utc};
-use chrono_tz::Europe::Budapest;
 use ics::{
-	Event, ICalendar,
-	properties::{Description, DtEnd, DtStart, Location, Summary},
+	Event, ToDo,
+	properties::{Description, LastModified, Location, Sequence, Summary},
 };
 use kreta_combine::CombinedLesson;
 use kreta_rs::client::Client;
 
-use crate::Options;
+use crate::{Options, categories};
 
 pub fn map_combined<'a>(
 	combined: impl IntoIterator<Item = &'a CombinedLesson>,
 	opts: &Options,
 ) -> impl Iterator<Item = anyhow::Result<Event<'a>>> {
-	let events = combined.into_iter().map(|lesson| {
+	let combined = combined.into_iter().collect::<Vec<_>>();
+	let first_lessons = crate::first_lessons(combined.iter().map(|lesson| &lesson.lesson));
+
+	let events = combined.into_iter().map(move |lesson| {
 		let extra_data = crate::ExtraData {
 			is_homework_included: true,
+			is_first_lesson: first_lessons.contains(lesson.lesson.uid.as_str()),
 			homework: lesson.homework.as_ref(),
 			exam: lesson.exam.as_ref(),
 			absence: lesson.absence.as_ref(),
 		};
 
-		crate::lesson_to_event_explicit(&lesson.lesson_raw, opts, extra_data)
+		crate::lesson_to_event_explicit(&lesson.lesson, opts, extra_data)
 			.with_context(|| format!("error while turning lesson into event\n{lesson:#?}"))
 	});
 	events
@@ -30,61 +34,110 @@ pub fn map_combined<'a>(
 
 pub fn collect_from_combined<'a>(
 	iter: impl IntoIterator<Item = anyhow::Result<Event<'a>>>,
+	calendar_name: Option<&str>,
+	opts: &Options,
+) -> anyhow::Result<String> {
+	collect_with_todos(iter, Vec::new(), calendar_name, opts)
+}
+
+/// [collect_from_combined], with `todos` added to the calendar too
+pub fn collect_with_todos<'a>(
+	iter: impl IntoIterator<Item = anyhow::Result<Event<'a>>>,
+	todos: Vec<ToDo<'a>>,
+	calendar_name: Option<&str>,
+	opts: &Options,
 ) -> anyhow::Result<String> {
-	let mut calendar = ICalendar::new("2.0", "timetable-to-ical");
+	let mut calendar = crate::new_calendar(calendar_name, opts);
 	for event in iter {
 		let event = event?;
 		calendar.add_event(event);
 	}
+	for todo in todos {
+		calendar.add_todo(todo);
+	}
 
 	Ok(calendar.to_string())
 }
 
 pub async fn combined_range_calendar_file(
 	client: &Client,
-	from: chrono::DateTime<chrono::Utc>,
-	to: chrono::DateTime<chrono::Utc>,
+	range: std::ops::Range<chrono::NaiveDate>,
 	opts: &Options,
+	calendar_name: Option<&str>,
 ) -> anyhow::Result<String> {
-	let preprocessed = kreta_combine::get_preprocessed_range(client, from, to)
+	let preprocessed = kreta_combine::get_preprocessed_range(client, range)
 		.await
 		.with_context(|| "while calling kreta_combine::get_preprocessed_range")?;
+	preprocessed_calendar_file(preprocessed, opts, calendar_name)
+}
 
-	let (combined, remaining_homework, remaining_exams) =
+#[cfg(feature = "cache")]
+/// [combined_range_calendar_file], but only what `cached` doesn't have is queried
+pub async fn combined_cached_calendar_file(
+	cached: &kreta_rs::cache::CachedClient<'_>,
+	range: std::ops::Range<chrono::NaiveDate>,
+	opts: &Options,
+	calendar_name: Option<&str>,
+) -> anyhow::Result<String> {
+	let preprocessed = kreta_combine::get_preprocessed_cached(cached, range)
+		.await
+		.with_context(|| "while calling kreta_combine::get_preprocessed_cached")?;
+	preprocessed_calendar_file(preprocessed, opts, calendar_name)
+}
+
+/// the lessons with their homework, exams and absences, and the homework & exams that didn't belong to any lesson
+pub fn preprocessed_calendar_file(
+	preprocessed: kreta_combine::Preprocessed,
+	opts: &Options,
+	calendar_name: Option<&str>,
+) -> anyhow::Result<String> {
+	let (combined, mut remaining_homework, remaining_exams) =
 		kreta_combine::match_preprocessed_with_remainder(preprocessed)?;
 
+	// every homework becomes a todo, the ones attached to lessons are still shown on the lessons too
+	let todos = if opts.homework_as_todo {
+		let mut seen = HashSet::new();
+		let todos = combined
+			.iter()
+			.filter_map(|lesson| lesson.homework.as_ref())
+			.chain(remaining_homework.values())
+			.filter(|homework| seen.insert(&homework.uid))
+			.map(|homework| crate::todo::homework_to_todo(homework, opts))
+			.collect();
+		remaining_homework = HashMap::new();
+		todos
+	} else {
+		Vec::new()
+	};
+
 	let iter = map_combined(combined.iter(), opts);
 
 	// -- add remaining entries
 	let remaining_homework_iter = remaining_homework.into_iter().map(|(_, homework)| {
-		let uid = uuid::Uuid::new_v4();
-		let uid = format!("{uid}");
-
-		let deadline: DateTime<Utc> = homework.date_deadline.parse().with_context(|| {
-			format!(
-				"while parsing homework deadline {} as a datetime",
-				homework.date_deadline
-			)
-		})?;
-		let deadline_day = deadline.with_timezone(&Budapest);
-		let deadline_day = deadline_day.format("%Y%m%d").to_string();
-
-		let mut event = Event::new(uid, deadline_day.clone());
-		event.push(Summary::new(format!(
-			"{} {}",
-			opts.homework_given_prefix, homework.subject_name
-		)));
-		event.push(DtStart::new(deadline_day.clone()));
-		event.push(DtEnd::new(deadline_day));
-
-		let date_assigned: DateTime<Utc> = homework.date_assigned.parse().with_context(|| {
-			format!(
-				"while parsing homework.date_assigned as DateTime: {}",
-				homework.date_assigned
-			)
-		})?;
-		let date_assigned = date_assigned.with_timezone(&Budapest);
-		let date_assigned = date_assigned.format("%Y %B %d");
+		let uid = crate::event_uid(opts, "homework", &homework.uid);
+		let dtstamp = crate::timestamp(homework.date_registered);
+
+		let mut event = Event::new(uid, dtstamp.clone());
+		event.push(LastModified::new(dtstamp));
+		event.push(Sequence::new(crate::SEQUENCE));
+		let summary = format!("{} {}", opts.homework_given_prefix, homework.subject_name);
+		if let Some(alarm) = opts.reminders.homework(homework.date_deadline, &summary) {
+			event.add_alarm(alarm);
+		}
+		event.push(Summary::new(summary));
+		let (dtstart, dtend) = crate::all_day(homework.date_deadline.date_naive());
+		event.push(dtstart);
+		event.push(dtend);
+		let event_categories = [
+			homework.subject.category.desc.as_str(),
+			categories::HOMEWORK,
+		];
+		if let Some(event_categories) = categories::categories(&event_categories) {
+			event.push(event_categories);
+		}
+		event.push(categories::color(&homework.subject));
+
+		let date_assigned = homework.date_assigned.format("%Y %B %d");
 
 		let pretty_print = if opts.pretty_print_as_desc {
 			format_args!("\n\n{homework:#?}")
@@ -103,29 +156,34 @@ pub async fn combined_range_calendar_file(
 	});
 
 	let remaining_exams_iter = remaining_exams.into_iter().map(|(_, exam)| {
-		let uid = uuid::Uuid::new_v4();
-		let uid = format!("{uid}");
+		let uid = crate::event_uid(opts, "exam", &exam.uid);
+		let dtstamp = crate::timestamp(exam.date_announced);
 
-		let date: DateTime<Utc> = exam
-			.date
-			.parse()
-			.with_context(|| format!("while parsing exam date {} as a datetime", exam.date))?;
-		let date = date.with_timezone(&Budapest);
-		let date_day = date.format("%Y%m%d").to_string();
-
-		let mut event = Event::new(uid, date_day.clone());
+		let mut event = Event::new(uid, dtstamp.clone());
+		event.push(LastModified::new(dtstamp));
+		event.push(Sequence::new(crate::SEQUENCE));
 		let pretty_print = if opts.pretty_print_as_desc {
 			format_args!("\n\n{exam:#?}")
 		} else {
 			format_args!("")
 		};
-		event.push(Summary::new(format!(
-			"{} {} - {}{pretty_print}",
+		let summary = format!(
+			"{} {} - {}",
 			opts.announced_exam_prefix, exam.subject_name, exam.topic
-		)));
-		event.push(DtStart::new(date_day.clone()));
-		event.push(DtEnd::new(date_day.clone()));
-		event.push(Location::new(exam.method.desc.clone()));
+		);
+		if let Some(alarm) = opts.reminders.exam(exam.date.date_naive(), &summary) {
+			event.add_alarm(alarm);
+		}
+		event.push(Summary::new(format!("{summary}{pretty_print}")));
+		let (dtstart, dtend) = crate::all_day(exam.date.date_naive());
+		event.push(dtstart);
+		event.push(dtend);
+		let event_categories = [exam.subject.category.desc.as_str(), categories::EXAM];
+		if let Some(event_categories) = categories::categories(&event_categories) {
+			event.push(event_categories);
+		}
+		event.push(categories::color(&exam.subject));
+		event.push(Location::new(exam.method_desc.clone()));
 
 		let desc = format!("{:#?}", exam);
 		event.push(Description::new(crate::escape_desc_text(&desc)));
@@ -137,6 +195,6 @@ pub async fn combined_range_calendar_file(
 		.chain(remaining_homework_iter)
 		.chain(remaining_exams_iter);
 
-	let timetable = collect_from_combined(iter_with_remainder)?;
+	let timetable = collect_with_todos(iter_with_remainder, todos, calendar_name, opts)?;
 	Ok(timetable)
 }
@@ -3,24 +3,29 @@ use std::fmt::{Debug, Display};
 use chrono::Utc;
 use ics::{
 	Event, ICalendar,
-	properties::{Description, DtEnd, DtStart, Location, Summary},
+	properties::{Description, Location, Summary},
 };
 
-/// T is a calendar. E is an error. if the result is an error, turn it into a calendar.
-pub fn result_as_timetable<E: Display + Debug>(res: Result<String, E>) -> String {
+use crate::Options;
+
+/// T is a calendar. E is an error. if the result is an error, turn it into a calendar. \
+/// `opts` is only used for [Options::uid_domain], so the error event has the same domain as every other event
+pub fn result_as_timetable<E: Display + Debug>(res: Result<String, E>, opts: &Options) -> String {
 	match res {
 		Ok(a) => a,
 		Err(err) => {
-			let today = Utc::now().date_naive();
-			let today = today.format("%Y%m%d").to_string();
+			let now = Utc::now();
+			let dtstamp = now.format(super::FORMAT_DATETIME).to_string();
+			let today = now.with_timezone(&kreta_rs::model::TIMEZONE).date_naive();
 
-			let uuid = uuid::Uuid::new_v4();
-			let uuid = format!("{uuid}");
+			// one error event per day, refreshing replaces it with the latest error
+			let uid = super::event_uid(opts, "error", &today.to_string());
 
-			let mut err_event = Event::new(&uuid, &today);
+			let mut err_event = Event::new(uid, dtstamp);
 			err_event.push(Summary::new("timetable error"));
-			err_event.push(DtStart::new(&today));
-			err_event.push(DtEnd::new(&today));
+			let (dtstart, dtend) = super::all_day(today);
+			err_event.push(dtstart);
+			err_event.push(dtend);
 			err_event.push(Location::new("see event notes for details"));
 
 			let notes = format!("{err}\n\n{err:#?}");
@@ -34,9 +39,12 @@ pub fn result_as_timetable<E: Display + Debug>(res: Result<String, E>) -> String
 	}
 }
 
-pub fn handle_timetable_err<E: Display + Debug, F: FnOnce() -> Result<String, E>>(f: F) -> String {
+pub fn handle_timetable_err<E: Display + Debug, F: FnOnce() -> Result<String, E>>(
+	f: F,
+	opts: &Options,
+) -> String {
 	let res = f();
-	result_as_timetable(res)
+	result_as_timetable(res, opts)
 }
 
 pub async fn handle_timetable_err_async<
@@ -44,7 +52,8 @@ pub async fn handle_timetable_err_async<
 	F: Future<Output = Result<String, E>>,
 >(
 	future: F,
+	opts: &Options,
 ) -> String {
 	let res = future.await;
-	result_as_timetable(res)
+	result_as_timetable(res, opts)
 }
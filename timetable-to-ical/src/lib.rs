@@ -1,27 +1,41 @@
-use std::borrow::Cow;
+use std::{
+	borrow::Cow,
+	collections::{HashMap, HashSet},
+};
 
 use anyhow::Context;
-use chrono::{DateTime, Utc};
-use chrono_tz::Europe::Budapest;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 use ics::{
-	Event, ICalendar,
-	properties::{Description, DtEnd, DtStart, Location, Summary},
+	Daylight, Event, ICalendar, Standard, TimeZone,
+	components::Property,
+	parameters::{TzIDParam, Value},
+	properties::{
+		Description, DtEnd, DtStart, LastModified, Location, Name, RRule, Sequence, Status,
+		Summary, TzName,
+	},
 };
-use kreta_rs::client::{
-	absences::AbsenceRaw, exam::ExamRaw, homework::HomeworkRaw, timetable::LessonRaw,
+use kreta_rs::{
+	client::{profile::StudentProfileRaw, timetable::LessonRaw},
+	model::{Absence, Exam, Homework, Lesson, LessonStatus, Presence, TIMEZONE},
 };
 
-pub mod absence_best_guess;
-use absence_best_guess::absence_guess;
-
 #[cfg(feature = "combine")]
 pub mod combine;
 
+pub mod categories;
 pub mod err;
 
-use crate::absence_best_guess::Absence;
+pub mod reminders;
+pub use reminders::Reminders;
+
+pub mod todo;
 
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(default)
+)]
 #[derive(Clone, Debug)]
 pub struct Options {
 	pub lowercase_subject_names: bool,
@@ -40,8 +54,22 @@ pub struct Options {
 	pub absence_prefix: Cow<'static, str>,
 	pub student_late_prefix: Cow<'static, str>,
 	// class info end
-	/// includes a pretty print (basic rust {:#?}) of the entire [LessonRaw] as notes
+	/// includes a pretty print (basic rust {:#?}) of the entire [Lesson] as notes
 	pub pretty_print_as_desc: bool,
+
+	/// write times in budapest time with a TZID (and the VTIMEZONE it refers to in the calendar) instead of utc
+	pub local_time: bool,
+
+	pub reminders: Reminders,
+
+	/// only used by [combine]: homework is also emitted as VTODOs (instead of all day events when it's not attached to a lesson),
+	/// for clients that can show a to-do list
+	pub homework_as_todo: bool,
+
+	/// goes after the @ in every event's uid, see [event_uid]. should be the domain of whoever's serving the calendar,
+	/// so it's not (de)serialized
+	#[cfg_attr(feature = "serde", serde(skip))]
+	pub uid_domain: Cow<'static, str>,
 }
 impl Default for Options {
 	fn default() -> Self {
@@ -50,12 +78,16 @@ impl Default for Options {
 			lesson_topic_in_name: true,
 			teacher_name_in_location: true,
 			pretty_print_as_desc: false,
+			local_time: false,
+			reminders: Default::default(),
+			homework_as_todo: false,
 			substitution_prefix: "🔄".into(),
 			cancelled_lesson_preifx: "❌".into(),
 			announced_exam_prefix: "📝".into(),
 			homework_given_prefix: "🏠".into(),
 			absence_prefix: "🚫".into(),
 			student_late_prefix: "⏰".into(),
+			uid_domain: "timetable-to-ical".into(),
 		}
 	}
 }
@@ -65,52 +97,50 @@ pub struct ExtraData<'a> {
 	/// false means show homework symbol where it's attached to the lesson (so it'll show up on the lesson it was given)
 	/// true means it'll show up when it's due (if it's attached in this struct)
 	is_homework_included: bool,
+	/// gets the first lesson reminder, see [first_lessons]
+	is_first_lesson: bool,
 
-	homework: Option<&'a HomeworkRaw>,
-	exam: Option<&'a ExamRaw>,
-	absence: Option<&'a AbsenceRaw>,
+	homework: Option<&'a Homework>,
+	exam: Option<&'a Exam>,
+	absence: Option<&'a Absence>,
 }
 
 const FORMAT_DATE: &str = "%Y%m%d";
 const FORMAT_DATETIME: &str = "%Y%m%dT%H%M%SZ";
+/// without the Z, for times with a TZID
+const FORMAT_DATETIME_LOCAL: &str = "%Y%m%dT%H%M%S";
 
 /// avoid throwing errors if possible
 pub fn lesson_to_event_explicit<'a>(
-	lesson: &'a LessonRaw,
+	lesson: &'a Lesson,
 	opts: &Options,
 	extra_data: ExtraData<'a>,
 ) -> anyhow::Result<Event<'a>> {
-	let uid = uuid::Uuid::new_v4();
-	let uid = format!("{uid}");
-
-	let (dtstamp, start_escaped, end_escaped) = {
-		let start: DateTime<Utc> = lesson
-			.start_time
-			.parse()
-			.with_context(|| format!("while parsing {} as a datetime", lesson.start_time))?;
-		let end: DateTime<Utc> = lesson
-			.end_time
-			.parse()
-			.with_context(|| format!("while parsing {} as a datetime", lesson.end_time))?;
-		// let (start, end) = (start.with_timezone(&Budapest), end.with_timezone(&Budapest));
-
-		if start == end {
-			// if start_time == end_time => make it an all day event (so far only seems to be school holidays n shit)
-			let start = start.with_timezone(&Budapest);
-			let event_time = start.format(FORMAT_DATE).to_string();
-			(
-				start.format("%Y%m%dT000000Z").to_string(),
-				event_time.clone(),
-				event_time,
-			)
-		} else {
-			let dtstamp = start.format("%Y%m%dT000000Z").to_string();
-			let start = start.format(FORMAT_DATETIME).to_string();
-			let end = end.format(FORMAT_DATETIME).to_string();
+	let uid = event_uid(opts, "lesson", &lesson.uid);
+
+	// attaching homework, an exam or an absence changes the event too, even if kreta didn't touch the lesson itself
+	let modified = [
+		extra_data.homework.map(|homework| homework.date_registered),
+		extra_data.exam.map(|exam| exam.date_announced),
+		extra_data.absence.map(|absence| absence.date_of_creation),
+	]
+	.into_iter()
+	.flatten()
+	.fold(lesson.last_modified, std::cmp::max);
+	let dtstamp = timestamp(modified);
+
+	let (dtstart, dtend) = if lesson.is_all_day() {
+		// if start_time == end_time => make it an all day event (so far only seems to be school holidays n shit)
+		all_day(lesson.start.date_naive())
+	} else {
+		event_times(lesson.start, lesson.end, opts)
+	};
 
-			(dtstamp, start, end)
-		}
+	let show_homework = match extra_data.is_homework_included {
+		false => lesson.homework_uid.is_some(),
+		true => extra_data.homework.is_some(),
 	};
+	let has_exam = lesson.announced_exam_uid.is_some() || extra_data.exam.is_some();
 
 	let name = {
 		let name_base: Cow<str> = if opts.lowercase_subject_names {
@@ -120,34 +150,26 @@ pub fn lesson_to_event_explicit<'a>(
 		};
 
 		let mut name_prefixes = String::new();
-		let absence = lesson
-			.student_presence
-			.as_ref()
-			.map(|a| absence_guess(&a.name))
-			.unwrap_or(Absence::Present);
-		match absence {
-			Absence::Absent => {
+		match &lesson.presence {
+			// anything we don't know is most likely some kind of absence
+			Some(Presence::Absent | Presence::Other(_)) => {
 				if opts.absence_prefix.len() > 0 {
 					name_prefixes.push_str(&opts.absence_prefix);
 				}
 			}
-			Absence::Late => {
+			Some(Presence::Late) => {
 				if opts.student_late_prefix.len() > 0 {
 					name_prefixes.push_str(&opts.student_late_prefix);
 				}
 			}
 			_ => {}
 		}
-		if lesson.status.uid.contains("Elmaradt") && opts.cancelled_lesson_preifx.len() > 0 {
+		if lesson.status == LessonStatus::Cancelled && opts.cancelled_lesson_preifx.len() > 0 {
 			name_prefixes.push_str(&opts.cancelled_lesson_preifx);
 		}
 		if lesson.announced_exam_uid.is_some() && opts.announced_exam_prefix.len() > 0 {
 			name_prefixes.push_str(&opts.announced_exam_prefix);
 		}
-		let show_homework = match extra_data.is_homework_included {
-			false => lesson.homework_uid.is_some(),
-			true => extra_data.homework.is_some(),
-		};
 		if show_homework && opts.homework_given_prefix.len() > 0 {
 			name_prefixes.push_str(&opts.homework_given_prefix);
 		}
@@ -202,12 +224,52 @@ pub fn lesson_to_event_explicit<'a>(
 		}
 	};
 
-	let mut event = Event::new(uid, dtstamp);
+	let mut event = Event::new(uid, dtstamp.clone());
+	event.push(LastModified::new(dtstamp));
+	event.push(Sequence::new(SEQUENCE));
+	let alarms = [
+		extra_data
+			.is_first_lesson
+			.then(|| opts.reminders.first_lesson(&name))
+			.flatten(),
+		has_exam
+			.then(|| opts.reminders.exam(lesson.date.date_naive(), &name))
+			.flatten(),
+		extra_data
+			.homework
+			.filter(|_| extra_data.is_homework_included)
+			.and_then(|homework| opts.reminders.homework(homework.date_deadline, &name)),
+	];
+	for alarm in alarms.into_iter().flatten() {
+		event.add_alarm(alarm);
+	}
+
 	event.push(Summary::new(name));
-	event.push(DtStart::new(start_escaped));
-	event.push(DtEnd::new(end_escaped));
+	event.push(dtstart);
+	event.push(dtend);
 	event.push(Location::new(location));
 
+	if lesson.is_cancelled() {
+		event.push(Status::cancelled());
+	}
+	let event_categories = [
+		Some(lesson.lesson_type.desc.as_str()),
+		lesson
+			.subject
+			.as_ref()
+			.map(|subject| subject.category.desc.as_str()),
+		lesson.is_substituted().then_some(categories::SUBSTITUTION),
+		has_exam.then_some(categories::EXAM),
+		show_homework.then_some(categories::HOMEWORK),
+	];
+	let event_categories = event_categories.into_iter().flatten().collect::<Vec<_>>();
+	if let Some(event_categories) = categories::categories(&event_categories) {
+		event.push(event_categories);
+	}
+	if let Some(subject) = &lesson.subject {
+		event.push(categories::color(subject));
+	}
+
 	let pretty_print = if opts.pretty_print_as_desc {
 		let desc = format!("{lesson:#?}\n\n{extra_data:#?}");
 		Some(desc)
@@ -224,7 +286,7 @@ pub fn lesson_to_event_explicit<'a>(
 			};
 			let excuse_line = match &absence.excuse_type {
 				Some(t) => format!("{} - {}", absence.excuse_status, t.desc),
-				None => absence.excuse_status.clone(),
+				None => absence.excuse_status.to_string(),
 			};
 
 			let block = format!("{absence_line}\n{excuse_line}\n\n");
@@ -236,22 +298,14 @@ pub fn lesson_to_event_explicit<'a>(
 		Some(exam) => {
 			info += &format!(
 				"{} {}\n{}\n\n",
-				opts.announced_exam_prefix, exam.topic, exam.method.desc
+				opts.announced_exam_prefix, exam.topic, exam.method_desc
 			);
 		}
 		None => {}
 	}
 	match extra_data.homework {
 		Some(homework) => {
-			let date_assigned: DateTime<Utc> =
-				homework.date_assigned.parse().with_context(|| {
-					format!(
-						"while parsing homework.date_assigned as DateTime: {}",
-						homework.date_assigned
-					)
-				})?;
-			let date_assigned = date_assigned.with_timezone(&Budapest);
-			let date_assigned = date_assigned.format("%Y %B %d");
+			let date_assigned = homework.date_assigned.format("%Y %B %d");
 			info += &format!(
 				"{}\n{}\n - {}, {date_assigned}\n\n",
 				opts.homework_given_prefix,
@@ -276,19 +330,33 @@ pub fn lesson_to_event_explicit<'a>(
 	Ok(event)
 }
 
-pub fn lesson_to_event<'a>(lesson: &'a LessonRaw, opts: &Options) -> anyhow::Result<Event<'a>> {
+pub fn lesson_to_event<'a>(lesson: &'a Lesson, opts: &Options) -> anyhow::Result<Event<'a>> {
 	lesson_to_event_explicit(lesson, opts, Default::default())
 }
 
 pub fn lessons_to_calendar_file_res<'a, I: IntoIterator<Item = &'a LessonRaw>>(
 	iter: I,
 	opts: &Options,
+	calendar_name: Option<&str>,
 ) -> anyhow::Result<String> {
-	let mut calendar = ICalendar::new("2.0", "timetable-to-ical");
-
-	let events_iter = iter
+	let lessons = iter
 		.into_iter()
-		.map(|lesson| (lesson, lesson_to_event(lesson, opts)));
+		.map(|lesson| {
+			Lesson::try_from(lesson.clone())
+				.with_context(|| format!("failed to convert lesson\n{lesson:#?}"))
+		})
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	let mut calendar = new_calendar(calendar_name, opts);
+
+	let first_lessons = first_lessons(&lessons);
+	let events_iter = lessons.iter().map(|lesson| {
+		let extra_data = ExtraData {
+			is_first_lesson: first_lessons.contains(lesson.uid.as_str()),
+			..Default::default()
+		};
+		(lesson, lesson_to_event_explicit(lesson, opts, extra_data))
+	});
 	for (lesson, event) in events_iter {
 		let event = event.with_context(|| {
 			format!("calling lesson_to_event returned an error\nlesson:\n{lesson:#?}")
@@ -303,8 +371,124 @@ pub fn lessons_to_calendar_file_res<'a, I: IntoIterator<Item = &'a LessonRaw>>(
 pub fn lessons_to_calendar_file<'a, I: IntoIterator<Item = &'a LessonRaw>>(
 	iter: I,
 	opts: &Options,
+	calendar_name: Option<&str>,
 ) -> String {
-	err::result_as_timetable(lessons_to_calendar_file_res(iter, opts))
+	err::result_as_timetable(
+		lessons_to_calendar_file_res(iter, opts, calendar_name),
+		opts,
+	)
+}
+
+/// the uids of the first lesson of every day, cancelled lessons and all day entries don't count
+pub fn first_lessons<'a>(lessons: impl IntoIterator<Item = &'a Lesson>) -> HashSet<&'a str> {
+	let mut first: HashMap<NaiveDate, &Lesson> = HashMap::new();
+	for lesson in lessons {
+		if lesson.is_cancelled() || lesson.is_all_day() {
+			continue;
+		}
+		let day = first.entry(lesson.start.date_naive()).or_insert(lesson);
+		if lesson.start < day.start {
+			*day = lesson;
+		}
+	}
+	first
+		.into_values()
+		.map(|lesson| lesson.uid.as_str())
+		.collect()
+}
+
+/// creates an empty calendar, named `calendar_name` if there is one \
+/// with [Options::local_time], it has the VTIMEZONE the events refer to
+pub fn new_calendar<'a>(calendar_name: Option<&str>, opts: &Options) -> ICalendar<'a> {
+	let mut calendar = ICalendar::new("2.0", "timetable-to-ical");
+	if let Some(name) = calendar_name {
+		let name = escape_desc_text(name);
+		// X-WR-CALNAME is what most clients actually read, NAME is the standard (rfc7986) one
+		calendar.push(Property::new("X-WR-CALNAME", name.clone()));
+		calendar.push(Name::new(name));
+	}
+	if opts.local_time {
+		calendar.add_timezone(budapest_timezone());
+	}
+	calendar
+}
+
+/// "student name (class) - school name", to be used as a calendar name
+pub fn calendar_name(profile: &StudentProfileRaw, class_name: Option<&str>) -> String {
+	match class_name {
+		Some(class_name) => format!("{} ({class_name}) - {}", profile.name, profile.inst_name),
+		None => format!("{} - {}", profile.name, profile.inst_name),
+	}
+}
+
+/// the uid of the event made from the kreta object with the uid `kreta_uid`, the same on every refresh
+/// so calendar apps update their events instead of dropping & adding them again \
+/// `kind` keeps lessons, homework & exams apart in case kreta reuses uids between them
+pub fn event_uid(opts: &Options, kind: &str, kreta_uid: &str) -> String {
+	let uuid = uuid::Uuid::new_v5(&UID_NAMESPACE, format!("{kind}/{kreta_uid}").as_bytes());
+	format!("{uuid}@{}", opts.uid_domain)
+}
+/// randomly generated once, so our uuids never collide with anyone else's v5 ones
+const UID_NAMESPACE: uuid::Uuid = uuid::uuid!("6f0c1f4e-5a3b-4d2e-9c71-3b8e2a9d4f10");
+
+/// DTSTAMP (also used as LAST-MODIFIED) of an event that last changed at `modified`
+fn timestamp(modified: DateTime<Tz>) -> String {
+	modified
+		.with_timezone(&Utc)
+		.format(FORMAT_DATETIME)
+		.to_string()
+}
+
+/// SEQUENCE of every event & todo \
+/// kreta doesn't count revisions of anything, so there's nothing to derive a real one from. clients see changes through
+/// DTSTAMP and LAST-MODIFIED instead, which are kreta's own modification times (see [timestamp])
+const SEQUENCE: &str = "0";
+
+/// DTSTART & DTEND of an event from `start` to `end`, in utc or budapest time depending on [Options::local_time]
+fn event_times<'a>(
+	start: DateTime<Tz>,
+	end: DateTime<Tz>,
+	opts: &Options,
+) -> (DtStart<'a>, DtEnd<'a>) {
+	if opts.local_time {
+		let tzid = || TzIDParam::new(TIMEZONE.name());
+		let mut dtstart = DtStart::new(start.format(FORMAT_DATETIME_LOCAL).to_string());
+		dtstart.add(tzid());
+		let mut dtend = DtEnd::new(end.format(FORMAT_DATETIME_LOCAL).to_string());
+		dtend.add(tzid());
+		(dtstart, dtend)
+	} else {
+		let (start, end) = (start.with_timezone(&Utc), end.with_timezone(&Utc));
+		(
+			DtStart::new(start.format(FORMAT_DATETIME).to_string()),
+			DtEnd::new(end.format(FORMAT_DATETIME).to_string()),
+		)
+	}
+}
+
+/// DTSTART & DTEND of an all day event on `day`. DTEND is exclusive, so it's the day after
+fn all_day<'a>(day: NaiveDate) -> (DtStart<'a>, DtEnd<'a>) {
+	let mut dtstart = DtStart::new(day.format(FORMAT_DATE).to_string());
+	dtstart.add(Value::DATE);
+	let next_day = day + chrono::Duration::days(1);
+	let mut dtend = DtEnd::new(next_day.format(FORMAT_DATE).to_string());
+	dtend.add(Value::DATE);
+	(dtstart, dtend)
+}
+
+/// the VTIMEZONE of [TIMEZONE], with the eu daylight saving rules hungary has followed since 1996
+fn budapest_timezone<'a>() -> TimeZone<'a> {
+	let mut daylight = Daylight::new("19700329T020000", "+0100", "+0200");
+	daylight.push(TzName::new("CEST"));
+	daylight.push(RRule::new("FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU"));
+
+	let mut standard = Standard::new("19701025T030000", "+0200", "+0100");
+	standard.push(TzName::new("CET"));
+	standard.push(RRule::new("FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU"));
+
+	let mut timezone = TimeZone::daylight(TIMEZONE.name(), daylight);
+	timezone.add_standard(standard);
+	timezone
 }
 
 // -- utils
@@ -316,3 +500,176 @@ fn escape_desc_text(input: &str) -> String {
 		.replace(',', "\\,")
 		.replace('\n', "\\n")
 }
+
+#[cfg(test)]
+mod tests {
+	use kreta_rs::client::homework::HomeworkRaw;
+
+	use super::*;
+
+	pub(crate) fn lessons() -> Vec<Lesson> {
+		let raw: Vec<LessonRaw> =
+			serde_json::from_str(include_str!("../../kreta-mock/fixtures/timetable.json")).unwrap();
+		raw.into_iter()
+			.map(|lesson| lesson.try_into().unwrap())
+			.collect()
+	}
+	pub(crate) fn homework() -> Vec<Homework> {
+		let raw: Vec<HomeworkRaw> =
+			serde_json::from_str(include_str!("../../kreta-mock/fixtures/homework.json")).unwrap();
+		raw.into_iter()
+			.map(|homework| homework.try_into().unwrap())
+			.collect()
+	}
+	/// the value of the first `name` property in `ics`, `name` includes the parameters
+	pub(crate) fn property(ics: &str, name: &str) -> String {
+		let prefix = format!("{name}:");
+		let line = ics.lines().find(|line| line.starts_with(&prefix));
+		line.unwrap_or_else(|| panic!("no {name} in\n{ics}"))[prefix.len()..].to_string()
+	}
+
+	#[test]
+	fn event_uid_is_stable() {
+		let opts = Options::default();
+		let uid = event_uid(&opts, "lesson", "1001");
+		// calendar apps remember this, it must never change
+		assert_eq!(
+			uid,
+			"5e16a7c4-2d31-5f74-b112-283e0709392f@timetable-to-ical"
+		);
+		assert_ne!(uid, event_uid(&opts, "homework", "1001"));
+
+		let opts = Options {
+			uid_domain: "example.com".into(),
+			..Default::default()
+		};
+		assert_eq!(
+			event_uid(&opts, "lesson", "1001"),
+			uid.replace("timetable-to-ical", "example.com")
+		);
+	}
+
+	#[test]
+	fn lesson_event_is_stamped_with_kretas_times() {
+		let opts = Options::default();
+		let lesson = &lessons()[0];
+		let event = lesson_to_event(lesson, &opts).unwrap().to_string();
+
+		assert_eq!(
+			property(&event, "UID"),
+			event_uid(&opts, "lesson", &lesson.uid)
+		);
+		// UtolsoModositas of the first lesson in the fixture
+		assert_eq!(property(&event, "LAST-MODIFIED"), "20260131T230000Z");
+		assert_eq!(property(&event, "DTSTAMP"), "20260131T230000Z");
+		assert_eq!(property(&event, "SEQUENCE"), "0");
+	}
+
+	#[test]
+	fn attachments_move_the_timestamps_not_the_sequence() {
+		let opts = Options::default();
+		let lesson = &lessons()[0];
+		let mut homework = homework().remove(0);
+		homework.date_registered = lesson.last_modified + chrono::Duration::days(1);
+		let extra_data = ExtraData {
+			homework: Some(&homework),
+			..Default::default()
+		};
+		let event = lesson_to_event_explicit(lesson, &opts, extra_data)
+			.unwrap()
+			.to_string();
+
+		let registered = timestamp(homework.date_registered);
+		assert_eq!(property(&event, "LAST-MODIFIED"), registered);
+		assert_eq!(property(&event, "DTSTAMP"), registered);
+		assert_eq!(property(&event, "SEQUENCE"), "0");
+	}
+
+	/// properties can only be printed as part of a component
+	fn times(dtstart: DtStart, dtend: DtEnd) -> String {
+		let mut event = Event::new("uid", "dtstamp");
+		event.push(dtstart);
+		event.push(dtend);
+		event.to_string()
+	}
+
+	#[test]
+	fn all_day_ends_the_next_day() {
+		let day = NaiveDate::from_ymd_opt(2026, 2, 28).unwrap();
+		let (dtstart, dtend) = all_day(day);
+		let event = times(dtstart, dtend);
+		assert_eq!(property(&event, "DTSTART;VALUE=DATE"), "20260228");
+		// DTEND is exclusive, and the day after the 28th of february isn't the 29th
+		assert_eq!(property(&event, "DTEND;VALUE=DATE"), "20260301");
+	}
+
+	#[test]
+	fn event_times_utc_or_local() {
+		let lesson = &lessons()[0];
+
+		let (dtstart, dtend) = event_times(lesson.start, lesson.end, &Options::default());
+		let event = times(dtstart, dtend);
+		assert_eq!(property(&event, "DTSTART"), "20260202T070000Z");
+		assert_eq!(property(&event, "DTEND"), "20260202T074500Z");
+
+		let opts = Options {
+			local_time: true,
+			..Default::default()
+		};
+		let (dtstart, dtend) = event_times(lesson.start, lesson.end, &opts);
+		let event = times(dtstart, dtend);
+		assert_eq!(
+			property(&event, "DTSTART;TZID=Europe/Budapest"),
+			"20260202T080000"
+		);
+		assert_eq!(
+			property(&event, "DTEND;TZID=Europe/Budapest"),
+			"20260202T084500"
+		);
+	}
+
+	#[test]
+	fn budapest_timezone_rules() {
+		let timezone = budapest_timezone().to_string();
+		assert_eq!(property(&timezone, "TZID"), "Europe/Budapest");
+
+		// daylight saving starts on the last sunday of march, ends on the last sunday of october
+		let daylight = &timezone[timezone.find("BEGIN:DAYLIGHT").unwrap()..];
+		assert_eq!(property(daylight, "TZOFFSETTO"), "+0200");
+		assert_eq!(
+			property(daylight, "RRULE"),
+			"FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU"
+		);
+		let standard = &timezone[timezone.find("BEGIN:STANDARD").unwrap()..];
+		assert_eq!(property(standard, "TZOFFSETTO"), "+0100");
+		assert_eq!(
+			property(standard, "RRULE"),
+			"FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU"
+		);
+	}
+
+	#[test]
+	fn first_lessons_skip_cancelled_and_all_day_ones() {
+		let mut lessons = lessons();
+		// kreta doesn't sort them
+		lessons.reverse();
+		let index = |uid: &str, lessons: &[Lesson]| {
+			lessons
+				.iter()
+				.position(|lesson| lesson.uid.starts_with(uid))
+				.unwrap()
+		};
+		let cancelled = index("1001", &lessons);
+		lessons[cancelled].status = LessonStatus::Cancelled;
+		let all_day = index("1011", &lessons);
+		lessons[all_day].end = lessons[all_day].start;
+
+		let first = first_lessons(&lessons);
+		let is_first = |uid: &str| first.iter().any(|first| first.starts_with(uid));
+		let days = lessons.iter().map(|lesson| lesson.start.date_naive());
+		assert_eq!(first.len(), days.collect::<HashSet<_>>().len());
+		assert!(!is_first("1001") && is_first("1002"));
+		assert!(!is_first("1011") && is_first("1012"));
+		assert!(is_first("1021") && !is_first("1022"));
+	}
+}
@@ -0,0 +1,91 @@
+//! reminders for the events, emitted as VALARMs
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use ics::{
+	Alarm,
+	parameters::Value,
+	properties::{Description, Trigger},
+};
+use kreta_rs::model::TIMEZONE;
+
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(default)
+)]
+#[derive(Clone, Debug, Default)]
+/// None turns a reminder off, they're all off by default
+pub struct Reminders {
+	/// minutes before the first lesson of every day (cancelled lessons don't count)
+	pub first_lesson_minutes: Option<u32>,
+	/// the hour (budapest time) on the day before an announced exam, like 18 for 6pm
+	pub exam_day_before_hour: Option<u32>,
+	/// hours before the day a homework is due starts
+	pub homework_hours: Option<u32>,
+}
+impl Reminders {
+	pub(crate) fn first_lesson<'a>(&self, description: &str) -> Option<Alarm<'a>> {
+		let minutes = self.first_lesson_minutes?;
+		let trigger = Trigger::new(format!("-PT{minutes}M"));
+		Some(alarm(trigger, description))
+	}
+
+	pub(crate) fn exam<'a>(&self, exam_day: NaiveDate, description: &str) -> Option<Alarm<'a>> {
+		let hour = self.exam_day_before_hour?;
+		let day_before = exam_day.pred_opt()?.and_hms_opt(hour, 0, 0)?;
+		// None if the time doesn't exist on that day because of daylight saving
+		let at = TIMEZONE.from_local_datetime(&day_before).earliest()?;
+		Some(alarm(absolute(at), description))
+	}
+
+	pub(crate) fn homework<'a>(
+		&self,
+		deadline: DateTime<Tz>,
+		description: &str,
+	) -> Option<Alarm<'a>> {
+		let hours = self.homework_hours?;
+		let at = deadline - chrono::Duration::hours(hours as _);
+		Some(alarm(absolute(at), description))
+	}
+}
+
+fn alarm<'a>(trigger: Trigger<'a>, description: &str) -> Alarm<'a> {
+	let description = crate::escape_desc_text(description);
+	Alarm::display(trigger, Description::new(description))
+}
+
+fn absolute<'a>(at: DateTime<Tz>) -> Trigger<'a> {
+	let at = at.with_timezone(&Utc).format(crate::FORMAT_DATETIME);
+	let mut trigger = Trigger::new(at.to_string());
+	trigger.add(Value::DATE_TIME);
+	trigger
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::property;
+
+	#[test]
+	fn exam_reminder_in_the_dst_gap() {
+		let reminders = Reminders {
+			exam_day_before_hour: Some(2),
+			..Default::default()
+		};
+		// the clocks go from 2:00 to 3:00 on 2026-03-29, the day before the exam
+		let exam_day = NaiveDate::from_ymd_opt(2026, 3, 30).unwrap();
+		assert!(reminders.exam(exam_day, "exam").is_none());
+
+		let reminders = Reminders {
+			exam_day_before_hour: Some(18),
+			..Default::default()
+		};
+		let alarm = reminders.exam(exam_day, "exam").unwrap().to_string();
+		// already summer time
+		assert_eq!(
+			property(&alarm, "TRIGGER;VALUE=DATE-TIME"),
+			"20260329T160000Z"
+		);
+	}
+}
@@ -0,0 +1,105 @@
+//! homework as VTODOs, for clients that can show them as a to-do list (see [Options::homework_as_todo])
+
+use ics::{
+	ToDo,
+	parameters::Value,
+	properties::{Description, DtStart, Due, LastModified, Sequence, Status, Summary},
+};
+use kreta_rs::model::Homework;
+
+use crate::{Options, categories};
+
+/// a VTODO that's due on the day of the deadline, completed if the student marked it as solved in kreta \
+/// stamped like every event, with the time kreta registered the homework: kreta doesn't say when homework was solved,
+/// so solving it only changes STATUS
+pub fn homework_to_todo<'a>(homework: &Homework, opts: &Options) -> ToDo<'a> {
+	let uid = crate::event_uid(opts, "todo", &homework.uid);
+	let dtstamp = crate::timestamp(homework.date_registered);
+
+	let mut todo = ToDo::new(uid, dtstamp.clone());
+	todo.push(LastModified::new(dtstamp));
+	todo.push(Sequence::new(crate::SEQUENCE));
+
+	let summary = format!("{} {}", opts.homework_given_prefix, homework.subject_name);
+	if let Some(alarm) = opts.reminders.homework(homework.date_deadline, &summary) {
+		todo.add_alarm(alarm);
+	}
+	todo.push(Summary::new(summary));
+
+	let (assigned, deadline) = (homework.date_assigned, homework.date_deadline);
+	// DUE has to be after DTSTART
+	if assigned.date_naive() < deadline.date_naive() {
+		let mut dtstart = DtStart::new(assigned.format(crate::FORMAT_DATE).to_string());
+		dtstart.add(Value::DATE);
+		todo.push(dtstart);
+	}
+	let mut due = Due::new(deadline.format(crate::FORMAT_DATE).to_string());
+	due.add(Value::DATE);
+	todo.push(due);
+
+	todo.push(match homework.is_solved {
+		true => Status::completed(),
+		false => Status::needs_action(),
+	});
+
+	let event_categories = [
+		homework.subject.category.desc.as_str(),
+		categories::HOMEWORK,
+	];
+	if let Some(event_categories) = categories::categories(&event_categories) {
+		todo.push(event_categories);
+	}
+	todo.push(categories::color(&homework.subject));
+
+	let pretty_print = if opts.pretty_print_as_desc {
+		format!("\n\n{homework:#?}")
+	} else {
+		String::new()
+	};
+	let desc = format!(
+		"{}\n - {}{pretty_print}",
+		homework.text_extract(),
+		homework.teachers_name
+	);
+	todo.push(Description::new(crate::escape_desc_text(&desc)));
+
+	todo
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{homework, property};
+
+	#[test]
+	fn solved_and_unsolved() {
+		let homework = homework();
+		let opts = Options::default();
+
+		let solved = homework.iter().find(|homework| homework.is_solved).unwrap();
+		let todo = homework_to_todo(solved, &opts).to_string();
+		assert_eq!(property(&todo, "STATUS"), "COMPLETED");
+		let registered = crate::timestamp(solved.date_registered);
+		assert_eq!(property(&todo, "DTSTAMP"), registered);
+		assert_eq!(property(&todo, "LAST-MODIFIED"), registered);
+		assert_eq!(property(&todo, "SEQUENCE"), "0");
+
+		let unsolved = homework
+			.iter()
+			.find(|homework| !homework.is_solved)
+			.unwrap();
+		let todo = homework_to_todo(unsolved, &opts).to_string();
+		assert_eq!(property(&todo, "STATUS"), "NEEDS-ACTION");
+		assert_eq!(
+			property(&todo, "DTSTAMP"),
+			crate::timestamp(unsolved.date_registered)
+		);
+		assert_eq!(property(&todo, "SEQUENCE"), "0");
+
+		// DUE has to be after DTSTART, yyyymmdd compares the same as the dates
+		let dtstart = property(&todo, "DTSTART;VALUE=DATE");
+		let due = property(&todo, "DUE;VALUE=DATE");
+		assert_eq!(due, unsolved.date_deadline.format("%Y%m%d").to_string());
+		assert!(dtstart < due, "{todo}");
+	}
+}